@@ -0,0 +1,25 @@
+use super::component_prelude::*;
+
+/// When the player enters it, they respawn at its position after dying.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// If this is the last checkpoint the player has entered.
+    pub is_active: bool,
+    /// Identifies the checkpoint in the `LevelTimer`'s splits.
+    /// Its position in the map, which, unlike its entity,
+    /// stays the same when the level is loaded again.
+    pub id:        String,
+}
+
+impl Component for Checkpoint {
+    type Storage = VecStorage<Self>;
+}
+
+impl Default for Checkpoint {
+    fn default() -> Self {
+        Self {
+            is_active: false,
+            id:        String::new(),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use super::component_prelude::*;
+
+/// An item (coin, gem, ...), which is removed and counted to the `Score`
+/// when the player touches it.
+#[derive(Serialize, Deserialize)]
+pub struct Collectible {
+    /// Unique id in its level, so it is only counted once per level.
+    pub id:       String,
+    /// Score added when collected.
+    pub value:    u32,
+    /// Name of the sound in `AudioHandles`, played when collected.
+    pub sound:    Option<String>,
+    /// Already collected in a previous run of the level.
+    /// Ghosts are drawn faded, and can't be collected again.
+    pub is_ghost: bool,
+}
+
+impl Component for Collectible {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,16 @@
+use super::component_prelude::*;
+
+/// A faded copy of the player, playing back the level's best run.
+/// It has no collision, so it can't interact with anything.
+#[derive(Serialize, Deserialize)]
+pub struct Ghost;
+
+impl Component for Ghost {
+    type Storage = NullStorage<Self>;
+}
+
+impl Default for Ghost {
+    fn default() -> Self {
+        Self
+    }
+}
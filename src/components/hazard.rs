@@ -0,0 +1,15 @@
+use super::component_prelude::*;
+
+/// Kills the player on touch (spikes, pits, ...).
+#[derive(Serialize, Deserialize)]
+pub struct Hazard;
+
+impl Component for Hazard {
+    type Storage = NullStorage<Self>;
+}
+
+impl Default for Hazard {
+    fn default() -> Self {
+        Self
+    }
+}
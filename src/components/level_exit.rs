@@ -0,0 +1,23 @@
+use super::component_prelude::*;
+
+/// When the player enters an entity with this component,
+/// the level with the given name is loaded.
+#[derive(Serialize, Deserialize)]
+pub struct LevelExit {
+    pub level: String,
+}
+
+impl LevelExit {
+    pub fn new<T>(level: T) -> Self
+    where
+        T: ToString,
+    {
+        Self {
+            level: level.to_string(),
+        }
+    }
+}
+
+impl Component for LevelExit {
+    type Storage = HashMapStorage<Self>;
+}
@@ -1,11 +1,29 @@
+mod checkpoint;
+mod collectible;
+mod ghost;
+mod hazard;
 mod jump_recharge;
+mod level_exit;
+mod moving_platform;
+mod one_way;
 mod player;
+mod player_animation;
+mod registry;
 
 pub mod prelude {
     pub use deathframe::components::prelude::*;
 
+    pub use super::Checkpoint;
+    pub use super::Collectible;
+    pub use super::Ghost;
+    pub use super::Hazard;
     pub use super::JumpRecharge;
+    pub use super::LevelExit;
+    pub use super::MovingPlatform;
+    pub use super::MovingPlatformMode;
+    pub use super::OneWay;
     pub use super::Player;
+    pub use super::PlayerAnimation;
 }
 
 mod component_prelude {
@@ -24,25 +42,19 @@ mod component_prelude {
     };
 }
 
+pub use checkpoint::Checkpoint;
+pub use collectible::Collectible;
+pub use ghost::Ghost;
+pub use hazard::Hazard;
 pub use jump_recharge::JumpRecharge;
+pub use level_exit::LevelExit;
+pub use moving_platform::{MovingPlatform, MovingPlatformMode};
+pub use one_way::OneWay;
 pub use player::Player;
-
-pub use deathframe::components::add_component_to_entity_by_name;
-
-use amethyst::ecs::EntityBuilder;
-use amethyst::prelude::Builder;
-
-pub fn add_component_to_entity_by_name_custom<'a, T>(
-    mut entity: EntityBuilder<'a>,
-    component_name: T,
-) -> EntityBuilder<'a>
-where
-    T: ToString,
-{
-    match component_name.to_string().as_str() {
-        "JumpRecharge" => entity = entity.with(jump_recharge::JumpRecharge),
-        _ => (),
-    }
-
-    entity
-}
+pub use player_animation::{AnimationClip, PlayerAnimation, PlayerAnimations};
+pub use registry::{
+    ComponentError,
+    ComponentRegistry,
+    MapComponent,
+    ParseComponent,
+};
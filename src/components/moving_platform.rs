@@ -0,0 +1,81 @@
+use deathframe::geo::Vector;
+
+use super::component_prelude::*;
+
+/// How a `MovingPlatform` continues after reaching its last waypoint.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MovingPlatformMode {
+    /// Move back through the waypoints in reverse order.
+    PingPong,
+    /// Move from the last waypoint straight back to the first.
+    Loop,
+}
+
+/// Moves the entity along its waypoints, at a constant speed.
+/// Players standing on top are carried along.
+#[derive(Serialize, Deserialize)]
+pub struct MovingPlatform {
+    /// Absolute positions to move through.
+    pub waypoints: Vec<Vector>,
+    /// Speed in units per second.
+    pub speed:     f32,
+    pub mode:      MovingPlatformMode,
+    /// The platform's velocity during the last tick.
+    pub velocity:  Vector,
+    /// The distance to move by, after the players have moved (see `MovePlatformsSystem`).
+    pub pending:   Vector,
+    next_waypoint: usize,
+    is_reversed:   bool,
+}
+
+impl MovingPlatform {
+    pub fn new(
+        waypoints: Vec<Vector>,
+        speed: f32,
+        mode: MovingPlatformMode,
+    ) -> Self {
+        Self {
+            waypoints:     waypoints,
+            speed:         speed,
+            mode:          mode,
+            velocity:      (0.0, 0.0).into(),
+            pending:       (0.0, 0.0).into(),
+            next_waypoint: 0,
+            is_reversed:   false,
+        }
+    }
+
+    /// Returns the waypoint the platform is currently moving towards.
+    pub fn next_waypoint(&self) -> Option<Vector> {
+        self.waypoints.get(self.next_waypoint).cloned()
+    }
+
+    /// Target the following waypoint, after the current one was reached.
+    pub fn advance(&mut self) {
+        let last = self.waypoints.len().saturating_sub(1);
+        if last == 0 {
+            return;
+        }
+        match self.mode {
+            MovingPlatformMode::Loop => {
+                self.next_waypoint = (self.next_waypoint + 1) % (last + 1);
+            }
+            MovingPlatformMode::PingPong => {
+                if self.next_waypoint == last {
+                    self.is_reversed = true;
+                } else if self.next_waypoint == 0 {
+                    self.is_reversed = false;
+                }
+                if self.is_reversed {
+                    self.next_waypoint -= 1;
+                } else {
+                    self.next_waypoint += 1;
+                }
+            }
+        }
+    }
+}
+
+impl Component for MovingPlatform {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,17 @@
+use super::component_prelude::*;
+
+/// A jump-through platform. It is only solid for players landing on it from above;
+/// the `OneWaySystem` adds and removes its `Solid` component accordingly.
+/// Players standing on it drop through when pressing down.
+#[derive(Serialize, Deserialize)]
+pub struct OneWay;
+
+impl Component for OneWay {
+    type Storage = NullStorage<Self>;
+}
+
+impl Default for OneWay {
+    fn default() -> Self {
+        Self
+    }
+}
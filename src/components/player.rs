@@ -1,18 +1,40 @@
+use amethyst::ecs::world::Index;
 use deathframe::geo::Vector;
 
 use super::component_prelude::*;
 
 #[derive(Serialize, Deserialize)]
 pub struct Player {
-    pub acceleration:        Vector,
-    pub run_acceleration:    Vector,
-    pub max_velocity:        (Option<f32>, Option<f32>),
-    pub run_max_velocity:    (Option<f32>, Option<f32>),
-    pub is_jump_button_down: bool,
-    pub is_run_button_down:  bool,
-    pub is_in_air:           bool,
-    pub is_on_wall:          bool,
-    pub has_double_jumped:   bool,
+    pub acceleration:            Vector,
+    pub run_acceleration:        Vector,
+    pub max_velocity:            (Option<f32>, Option<f32>),
+    pub run_max_velocity:        (Option<f32>, Option<f32>),
+    pub is_jump_button_down:     bool,
+    pub is_run_button_down:      bool,
+    pub is_in_air:               bool,
+    pub is_on_wall:              bool,
+    pub has_double_jumped:       bool,
+    /// Seconds left, in which a ground jump is still possible after leaving the ground.
+    pub coyote_timer:            f32,
+    /// Seconds left, in which a buffered jump press is executed when landing.
+    pub jump_buffer_timer:       f32,
+    /// Seconds left, in which horizontal input is ignored after a wall jump.
+    pub wall_jump_lockout_timer: f32,
+    /// The solid entity the player is standing on, if any.
+    /// Moving platforms carry the player standing on them.
+    pub standing_on:             Option<Index>,
+    /// The `OneWay` platform the player is dropping through, if any.
+    pub dropping_through:        Option<Index>,
+    /// The velocity of the moving platform the player is standing on,
+    /// added to their velocity while the `MoveEntitiesSystem` moves them.
+    pub carried_velocity:        Vector,
+    /// Where the player respawns after dying;
+    /// their spawn point, or the position of the last entered `Checkpoint`.
+    pub spawn_point:             Vector,
+    /// The last entered `Checkpoint`, if any.
+    pub checkpoint:              Option<Index>,
+    /// Seconds left until respawning, while the player is dead.
+    pub death_timer:             Option<f32>,
 }
 
 impl Player {
@@ -28,6 +50,14 @@ impl Player {
         }
     }
 
+    pub fn current_max_velocity(&self) -> (Option<f32>, Option<f32>) {
+        if self.is_run_button_down {
+            self.run_max_velocity
+        } else {
+            self.max_velocity
+        }
+    }
+
     pub fn on_ground(&self) -> bool {
         !self.is_in_air
     }
@@ -39,6 +69,10 @@ impl Player {
     pub fn on_wall(&self) -> bool {
         self.is_on_wall
     }
+
+    pub fn is_dead(&self) -> bool {
+        self.death_timer.is_some()
+    }
 }
 
 pub struct PlayerBuilder {
@@ -46,6 +80,7 @@ pub struct PlayerBuilder {
     run_acceleration: Option<Vector>,
     max_velocity:     Option<(Option<f32>, Option<f32>)>,
     run_max_velocity: Option<(Option<f32>, Option<f32>)>,
+    spawn_point:      Option<Vector>,
 }
 
 impl PlayerBuilder {
@@ -75,6 +110,11 @@ impl PlayerBuilder {
         self
     }
 
+    pub fn spawn_point(mut self, spawn_point: Vector) -> Self {
+        self.spawn_point = Some(spawn_point);
+        self
+    }
+
     pub fn build(self) -> Player {
         let default = Player::default();
         Player {
@@ -86,6 +126,7 @@ impl PlayerBuilder {
             run_max_velocity: self
                 .run_max_velocity
                 .unwrap_or(default.run_max_velocity),
+            spawn_point: self.spawn_point.unwrap_or(default.spawn_point),
             ..default
         }
     }
@@ -98,6 +139,7 @@ impl Default for PlayerBuilder {
             run_acceleration: None,
             max_velocity:     None,
             run_max_velocity: None,
+            spawn_point:      None,
         }
     }
 }
@@ -109,15 +151,24 @@ impl Component for Player {
 impl Default for Player {
     fn default() -> Self {
         Self {
-            acceleration:        (1500.0, 0.0).into(),
-            run_acceleration:    (2000.0, 0.0).into(),
-            max_velocity:        (Some(400.0), None),
-            run_max_velocity:    (Some(800.0), None),
-            is_jump_button_down: false,
-            is_run_button_down:  false,
-            is_in_air:           false,
-            is_on_wall:          false,
-            has_double_jumped:   false,
+            acceleration:            (1500.0, 0.0).into(),
+            run_acceleration:        (2000.0, 0.0).into(),
+            max_velocity:            (Some(400.0), None),
+            run_max_velocity:        (Some(800.0), None),
+            is_jump_button_down:     false,
+            is_run_button_down:      false,
+            is_in_air:               false,
+            is_on_wall:              false,
+            has_double_jumped:       false,
+            coyote_timer:            0.0,
+            jump_buffer_timer:       0.0,
+            wall_jump_lockout_timer: 0.0,
+            standing_on:             None,
+            dropping_through:        None,
+            carried_velocity:        (0.0, 0.0).into(),
+            spawn_point:             (0.0, 0.0).into(),
+            checkpoint:              None,
+            death_timer:             None,
         }
     }
 }
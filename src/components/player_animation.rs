@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use super::component_prelude::*;
+use crate::resource_helpers::*;
+
+/// A named animation clip; see `resources/textures/spritesheet_player_animations.ron`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationClip {
+    pub sprite_ids: Vec<usize>,
+    pub delay_ms:   u64,
+    pub looping:    bool,
+}
+
+/// The player's animation clips, as defined in the RON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerAnimations {
+    /// Multiplier for the `run` clip's speed, while the run button is held.
+    pub run_button_speed: f32,
+    pub clips:            HashMap<String, AnimationClip>,
+}
+
+impl PlayerAnimations {
+    /// Load the clips from `resources/textures/spritesheet_player_animations.ron`.
+    pub fn load() -> Result<Self, String> {
+        let path = resource("textures/spritesheet_player_animations.ron");
+        let animations_raw = read_file(&path)
+            .map_err(|e| format!("Couldn't read '{}': {}", path, e))?;
+        ron::Value::from_str(&animations_raw)
+            .and_then(|value| value.into_rust())
+            .map_err(|e| format!("Couldn't parse '{}': {}", path, e))
+    }
+}
+
+/// Plays the player's animation clips; the `PlayerAnimationSystem` chooses
+/// the clip from the player's state, and which way the sprite faces.
+#[derive(Serialize, Deserialize)]
+pub struct PlayerAnimation {
+    pub animations:  PlayerAnimations,
+    /// Name of the playing clip.
+    pub clip:        String,
+    /// Index into the playing clip's `sprite_ids`.
+    pub frame:       usize,
+    /// Milliseconds, since the current frame was shown.
+    pub elapsed_ms:  f32,
+    pub facing_left: bool,
+}
+
+impl PlayerAnimation {
+    pub fn new(animations: PlayerAnimations) -> Self {
+        Self {
+            animations:  animations,
+            clip:        "idle".to_string(),
+            frame:       0,
+            elapsed_ms:  0.0,
+            facing_left: false,
+        }
+    }
+
+    /// Switch to the clip with the given name, starting from its first sprite.
+    /// Nothing happens, if the clip is already playing or doesn't exist.
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip && self.animations.clips.contains_key(clip) {
+            self.clip = clip.to_string();
+            self.frame = 0;
+            self.elapsed_ms = 0.0;
+        }
+    }
+
+    /// Advance the playing clip by the given milliseconds,
+    /// and return the sprite id to show.
+    pub fn update(&mut self, delta_ms: f32) -> Option<usize> {
+        let clip = self.animations.clips.get(&self.clip)?;
+        if clip.sprite_ids.is_empty() {
+            return None;
+        }
+        self.elapsed_ms += delta_ms;
+        let delay_ms = clip.delay_ms.max(1) as f32;
+        while self.elapsed_ms >= delay_ms {
+            self.elapsed_ms -= delay_ms;
+            if self.frame + 1 < clip.sprite_ids.len() {
+                self.frame += 1;
+            } else if clip.looping {
+                self.frame = 0;
+            }
+        }
+        clip.sprite_ids.get(self.frame).cloned()
+    }
+}
+
+impl Component for PlayerAnimation {
+    type Storage = HashMapStorage<Self>;
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use amethyst::ecs::EntityBuilder;
+use amethyst::prelude::Builder;
+use deathframe::geo::Vector;
+use json::JsonValue;
+
+use super::component_prelude::*;
+use super::prelude::*;
+
+/// A component parsed from a map, which can be added to any amount of entities.
+pub trait MapComponent: Send + Sync {
+    fn add_to<'a>(&self, entity: EntityBuilder<'a>) -> EntityBuilder<'a>;
+}
+
+/// Any function creating a component is a `MapComponent`.
+impl<F, C> MapComponent for F
+where
+    F: Fn() -> C + Send + Sync,
+    C: Component + Send + Sync,
+{
+    fn add_to<'a>(&self, entity: EntityBuilder<'a>) -> EntityBuilder<'a> {
+        entity.with(self())
+    }
+}
+
+/// Parses a component's arguments to a `MapComponent`.
+/// Returns an error message, if the arguments are invalid.
+pub type ParseComponent =
+    fn(&JsonValue) -> Result<Box<dyn MapComponent>, String>;
+
+#[derive(Debug)]
+pub enum ComponentError {
+    /// No component is registered with the given name.
+    Unknown { name: String },
+    /// The component's arguments are invalid.
+    Invalid { name: String, error: String },
+}
+
+/// Maps component names, as used in the `components` property of tiles and objects
+/// in Tiled, to functions parsing the component from its arguments.
+///
+/// A component is given either by its name, in which case its arguments are read from
+/// the tile's or object's properties (`"Gravity"`, with the property `gravity: "0.0, -100.0"`),
+/// or by its name followed by a JSON object with its arguments (`"Gravity{\"x\":0.0,\"y\":-100.0}"`).
+pub struct ComponentRegistry {
+    components: HashMap<&'static str, ParseComponent>,
+}
+
+impl ComponentRegistry {
+    /// Create an empty registry, without any components.
+    pub fn new() -> Self {
+        Self {
+            components: HashMap::new(),
+        }
+    }
+
+    /// Register a component with the given name.
+    /// A previously registered component with the same name is replaced.
+    pub fn register(
+        mut self,
+        name: &'static str,
+        parse: ParseComponent,
+    ) -> Self {
+        self.components.insert(name, parse);
+        self
+    }
+
+    /// Parse the given component string, with the given tile's or object's properties.
+    pub fn parse(
+        &self,
+        component: &str,
+        properties: &JsonValue,
+    ) -> Result<Box<dyn MapComponent>, ComponentError> {
+        let (name, inline_args) = match component.find('{') {
+            Some(index) => (&component[..index], Some(&component[index..])),
+            None => (component, None),
+        };
+        let name = name.trim();
+        let invalid = |error: String| ComponentError::Invalid {
+            name: name.to_string(),
+            error,
+        };
+
+        let parse = self.components.get(name).ok_or_else(|| {
+            ComponentError::Unknown {
+                name: name.to_string(),
+            }
+        })?;
+
+        match inline_args {
+            Some(args) => {
+                let args = json::parse(args)
+                    .map_err(|e| invalid(format!("invalid JSON: {}", e)))?;
+                parse(&args).map_err(invalid)
+            }
+            None => parse(properties).map_err(invalid),
+        }
+    }
+}
+
+/// Registers all components, which can be used in maps.
+impl Default for ComponentRegistry {
+    fn default() -> Self {
+        Self::new()
+            .register("Velocity", parse_velocity)
+            .register("MaxVelocity", parse_max_velocity)
+            .register("DecreaseVelocity", parse_decrease_velocity)
+            .register("Gravity", parse_gravity)
+            .register("Solid", |_| Ok(Box::new(|| Solid)))
+            .register("Collision", |_| Ok(Box::new(Collision::new)))
+            .register("CheckCollision", |_| Ok(Box::new(|| CheckCollision)))
+            .register("Push", |_| Ok(Box::new(|| Push)))
+            .register("Pushable", |_| Ok(Box::new(|| Pushable)))
+            .register("JumpRecharge", |_| Ok(Box::new(|| JumpRecharge)))
+            .register("Hazard", |_| Ok(Box::new(|| Hazard)))
+            .register("OneWay", |_| Ok(Box::new(|| OneWay)))
+    }
+}
+
+impl fmt::Display for ComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ComponentError::Unknown { name } => {
+                write!(f, "unknown component '{}'", name)
+            }
+            ComponentError::Invalid { name, error } => {
+                write!(f, "invalid component '{}': {}", name, error)
+            }
+        }
+    }
+}
+
+impl Error for ComponentError {
+}
+
+fn parse_velocity(args: &JsonValue) -> Result<Box<dyn MapComponent>, String> {
+    let initial = optional_vector_arg(args, "velocity")?;
+    Ok(Box::new(move || {
+        let mut velocity = Velocity::default();
+        if let Some((x, y)) = initial {
+            velocity.x = x;
+            velocity.y = y;
+        }
+        velocity
+    }))
+}
+
+fn parse_max_velocity(
+    args: &JsonValue,
+) -> Result<Box<dyn MapComponent>, String> {
+    let max_velocity = vector_arg(args, "max_velocity")?;
+    Ok(Box::new(move || MaxVelocity::from(max_velocity)))
+}
+
+fn parse_decrease_velocity(
+    args: &JsonValue,
+) -> Result<Box<dyn MapComponent>, String> {
+    let decrease_velocity = vector_arg(args, "decrease_velocity")?;
+    Ok(Box::new(move || DecreaseVelocity::from(decrease_velocity)))
+}
+
+fn parse_gravity(args: &JsonValue) -> Result<Box<dyn MapComponent>, String> {
+    let gravity = vector_arg(args, "gravity")?;
+    Ok(Box::new(move || Gravity::from(gravity)))
+}
+
+/// Like `optional_vector_arg`, but the vector is required.
+fn vector_arg(args: &JsonValue, key: &str) -> Result<Vector, String> {
+    optional_vector_arg(args, key)?
+        .ok_or_else(|| format!("missing vector argument '{}'", key))
+}
+
+/// Reads a vector argument, either from the `x` and `y` fields of inline arguments
+/// (`{"x": 1.0, "y": 2.0}`), or from the given property as a string (`"1.0, 2.0"`).
+fn optional_vector_arg(
+    args: &JsonValue,
+    key: &str,
+) -> Result<Option<Vector>, String> {
+    if let (Some(x), Some(y)) = (args["x"].as_f32(), args["y"].as_f32()) {
+        return Ok(Some((x, y).into()));
+    }
+    match &args[key] {
+        JsonValue::Null => Ok(None),
+        value => value
+            .as_str()
+            .and_then(|string| {
+                let vec = string
+                    .split(",")
+                    .map(|s| s.trim().parse::<f32>().ok())
+                    .collect::<Option<Vec<f32>>>()?;
+                if vec.len() == 2 {
+                    Some(Some((vec[0], vec[1]).into()))
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| {
+                format!(
+                    "argument '{}' is not a vector \"x, y\": '{}'",
+                    key,
+                    value.dump()
+                )
+            }),
+    }
+}
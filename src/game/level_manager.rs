@@ -0,0 +1,282 @@
+use amethyst::core::transform::Transform;
+use amethyst::ecs::{Builder, Entity, Join, World};
+use amethyst::renderer::{Rgba, SpriteRender, Transparent};
+use deathframe::components::{ScaleOnce, Size};
+use deathframe::geo::Vector;
+
+use super::{MapLoadError, MapLoader};
+use crate::components::{Ghost, Player};
+use crate::ghost::{GhostRun, Ghosts};
+use crate::level_exit::LevelExitReached;
+use crate::level_timer::LevelTimer;
+use crate::replay::ReplayMode;
+use crate::resource_helpers::*;
+use crate::save_data::SaveData;
+use crate::score::Score;
+use crate::settings::Settings;
+
+/// The list of levels, as defined in `resources/levels.ron`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LevelManifest {
+    pub levels: Vec<LevelData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelData {
+    pub name: String,
+    pub map:  String,
+}
+
+/// Tint of the ghost, which plays back the best run.
+const GHOST_TINT: Rgba = Rgba(1.0, 1.0, 1.0, 0.4);
+
+/// Loads levels from the manifest, and tears down the currently running level
+/// before building the next one.
+pub struct LevelManager {
+    manifest:      LevelManifest,
+    current_level: Option<String>,
+    map_loader:    MapLoader,
+    ghost_entity:  Option<Entity>,
+    /// If the current level's best run is shown as a ghost;
+    /// the ghost setting, when it was last applied.
+    ghost_shown:   bool,
+}
+
+impl LevelManager {
+    pub fn new(manifest: LevelManifest) -> Self {
+        Self {
+            manifest:      manifest,
+            current_level: None,
+            map_loader:    MapLoader::new(),
+            ghost_entity:  None,
+            ghost_shown:   false,
+        }
+    }
+
+    /// Returns the name of the currently loaded level.
+    pub fn current_level(&self) -> Option<&str> {
+        self.current_level.as_ref().map(String::as_str)
+    }
+
+    /// Returns `true` if the current level's entities have all been built.
+    pub fn is_finished_loading(&self) -> bool {
+        self.map_loader.is_finished()
+    }
+
+    /// Start a new run on the first level from the manifest.
+    pub fn load_first_level(
+        &mut self,
+        world: &mut World,
+    ) -> Result<(), MapLoadError> {
+        let name = self
+            .manifest
+            .levels
+            .first()
+            .map(|level| level.name.clone())
+            .ok_or(MapLoadError::NoLevels)?;
+        self.start_level(name, world)
+    }
+
+    /// Start a new run on the level with the given name, see `load_level`.
+    /// The input recording, if recording, starts over with this level.
+    pub fn start_level<T>(
+        &mut self,
+        name: T,
+        world: &mut World,
+    ) -> Result<(), MapLoadError>
+    where
+        T: ToString,
+    {
+        let name = name.to_string();
+        self.load_level(&name, world)?;
+        let tick_rate = world.read_resource::<Settings>().physics.tick_rate;
+        world
+            .write_resource::<ReplayMode>()
+            .restart(&name, tick_rate);
+        Ok(())
+    }
+
+    /// Load the level with the given name.
+    /// The map is parsed first; only if that succeeds, the current level is unloaded
+    /// and the new one is built. On error, the current level keeps running.
+    pub fn load_level<T>(
+        &mut self,
+        name: T,
+        world: &mut World,
+    ) -> Result<(), MapLoadError>
+    where
+        T: ToString,
+    {
+        let name = name.to_string();
+        let map_loader = self.parse_level(&name)?;
+        self.build_level(name, map_loader, world);
+        Ok(())
+    }
+
+    /// Parse the map of the level with the given name, without building it.
+    /// Returns an error if the level isn't in the manifest, or its map can't be loaded.
+    pub fn parse_level(&self, name: &str) -> Result<MapLoader, MapLoadError> {
+        let map = self
+            .manifest
+            .levels
+            .iter()
+            .find(|level| level.name == name)
+            .map(|level| level.map.clone())
+            .ok_or_else(|| MapLoadError::UnknownLevel {
+                level: name.to_string(),
+            })?;
+
+        let mut map_loader = MapLoader::new();
+        map_loader.load_map(map)?;
+        Ok(map_loader)
+    }
+
+    /// Unload the current level, and build the level with the given name,
+    /// from its map parsed by `parse_level`.
+    pub fn build_level(
+        &mut self,
+        name: String,
+        mut map_loader: MapLoader,
+        world: &mut World,
+    ) {
+        self.unload_level(world);
+        world.write_resource::<SaveData>().unlock_level(&name);
+        // Collectibles check the level's `Score`, when they are built
+        world.write_resource::<Score>().set_level(&name);
+        map_loader.build(world);
+        let best = world
+            .read_resource::<SaveData>()
+            .best_times
+            .get(&name)
+            .cloned();
+        world.write_resource::<LevelTimer>().reset(best);
+        world.write_resource::<LevelExitReached>().take();
+        self.map_loader = map_loader;
+        self.load_ghost(&name, world);
+        self.current_level = Some(name);
+    }
+
+    /// Load the current level again, from the start.
+    pub fn restart_level(
+        &mut self,
+        world: &mut World,
+    ) -> Result<(), MapLoadError> {
+        match self.current_level.clone() {
+            Some(name) => self.start_level(name, world),
+            None => self.load_first_level(world),
+        }
+    }
+
+    /// Delete all entities of the current level.
+    pub fn unload_level(&mut self, world: &mut World) {
+        self.map_loader.unload(world);
+        self.hide_ghost(world);
+        self.current_level = None;
+    }
+
+    /// Create or delete the current level's ghost, if ghosts were turned on or off
+    /// in the options since the level was loaded.
+    pub fn apply_ghost_setting(&mut self, world: &mut World) {
+        let show_ghosts = world.read_resource::<SaveData>().show_ghosts();
+        if show_ghosts == self.ghost_shown {
+            return;
+        }
+        if let Some(level) = self.current_level.clone() {
+            if show_ghosts {
+                self.show_ghost(&level, world);
+            } else {
+                self.hide_ghost(world);
+            }
+        }
+    }
+
+    /// Start recording a new run, and show the level's best run, if ghosts are shown.
+    fn load_ghost(&mut self, level: &str, world: &mut World) {
+        *world.write_resource::<Ghosts>() = Ghosts::default();
+        if world.read_resource::<SaveData>().show_ghosts() {
+            self.show_ghost(level, world);
+        }
+    }
+
+    /// Load the level's best run, and create a ghost of the player playing it back,
+    /// if there is a best run. The ghost starts at the best run's frame
+    /// for the level timer's current tick, so it is in sync, when shown mid-level.
+    fn show_ghost(&mut self, level: &str, world: &mut World) {
+        let best = GhostRun::load(level);
+        let has_best = best.is_some();
+        world.write_resource::<Ghosts>().best = best;
+        if has_best {
+            self.ghost_entity = create_ghost(world);
+        }
+        self.ghost_shown = true;
+    }
+
+    /// Delete the ghost, and stop playing back the best run.
+    fn hide_ghost(&mut self, world: &mut World) {
+        if let Some(entity) = self.ghost_entity.take() {
+            world.delete_entity(entity).unwrap();
+        }
+        world.write_resource::<Ghosts>().best = None;
+        self.ghost_shown = false;
+    }
+}
+
+/// Create a ghost, looking like the player, but without any collision,
+/// at the best run's frame for the level timer's current tick.
+/// Returns `None`, if the level has no player.
+fn create_ghost(world: &mut World) -> Option<Entity> {
+    let (mut transform, size, mut sprite_render_opt) = {
+        let players = world.read_storage::<Player>();
+        let transforms = world.read_storage::<Transform>();
+        let sizes = world.read_storage::<Size>();
+        let sprite_renders = world.read_storage::<SpriteRender>();
+        (&players, &transforms, &sizes, sprite_renders.maybe())
+            .join()
+            .next()
+            .map(|(_, transform, size, sprite_render_opt)| {
+                (
+                    transform.clone(),
+                    Size::from(Vector::from((size.w, size.h))),
+                    sprite_render_opt.cloned(),
+                )
+            })?
+    };
+
+    let tick = world.read_resource::<LevelTimer>().ticks();
+    if let Some(frame) = world
+        .read_resource::<Ghosts>()
+        .best
+        .as_ref()
+        .and_then(|best| best.frame_at(tick))
+    {
+        transform.set_x(frame.pos.0);
+        transform.set_y(frame.pos.1);
+        if let Some(sprite_render) = sprite_render_opt.as_mut() {
+            sprite_render.sprite_number = frame.sprite;
+        }
+    }
+
+    let mut ghost = world
+        .create_entity()
+        .with(Ghost)
+        .with(transform)
+        .with(size)
+        .with(ScaleOnce)
+        .with(Transparent)
+        .with(GHOST_TINT);
+    if let Some(sprite_render) = sprite_render_opt {
+        ghost = ghost.with(sprite_render);
+    }
+    Some(ghost.build())
+}
+
+/// Load the level list from `resources/levels.ron`.
+pub fn load_level_manifest() -> Result<LevelManifest, MapLoadError> {
+    let path = resource("levels.ron");
+    let manifest_raw = read_file(&path).map_err(|error| MapLoadError::Io {
+        path: path.clone(),
+        error,
+    })?;
+    ron::de::from_str(&manifest_raw)
+        .map_err(|error| MapLoadError::Ron { path, error })
+}
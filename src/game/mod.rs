@@ -1,6 +1,14 @@
+mod level_manager;
 // TODO: Temporary pub.
 pub mod states;
 
 pub use deathframe::handlers;
 
+pub use level_manager::{
+    load_level_manifest,
+    LevelData,
+    LevelManager,
+    LevelManifest,
+};
 pub use states::prelude::*;
+pub use states::startup::map_loader::{MapLoadError, MapLoader};
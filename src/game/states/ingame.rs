@@ -1,9 +1,100 @@
+use std::mem;
+
+use amethyst::core::timing::Time;
+use amethyst::input::InputHandler;
+
 use super::state_prelude::*;
-use super::Paused;
+use super::{IngameRequest, MainMenuError, Paused};
+use crate::game::LevelManager;
+use crate::ghost::Ghosts;
+use crate::level_exit::LevelExitReached;
+use crate::level_timer::LevelTimer;
+use crate::physics::PhysicsDispatcher;
+use crate::player_input::PlayerInput;
+use crate::replay::ReplayMode;
+use crate::save_data::{save_game, SaveData};
+use crate::settings::prelude::*;
+use crate::ui_buttons::UiButtons;
 
-pub struct Ingame;
+pub struct Ingame {
+    level_manager:      LevelManager,
+    physics:            Option<PhysicsDispatcher<'static, 'static>>,
+    hud_entity:         Option<Entity>,
+    /// After starting or resuming from a menu, the player's input is ignored
+    /// until `ui_select` is released, so the button press doesn't also jump.
+    is_ignoring_select: bool,
+}
 
 impl<'a, 'b> Ingame {
+    pub fn new(level_manager: LevelManager) -> Self {
+        Self {
+            level_manager:      level_manager,
+            physics:            None,
+            hud_entity:         None,
+            is_ignoring_select: true,
+        }
+    }
+
+    fn create_hud(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        self.hud_entity = Some(data.world.exec(|mut creator: UiCreator| {
+            creator.create(resource("ui/hud.ron"), ())
+        }));
+    }
+
+    fn delete_hud(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        if let Some(entity) = self.hud_entity.take() {
+            data.world.delete_entity(entity).unwrap();
+        }
+    }
+
+    /// Clear the player's input, while the menu's `ui_select` press is still held.
+    fn consume_ui_select(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        if !self.is_ignoring_select {
+            return;
+        }
+        let is_select_down = data
+            .world
+            .read_resource::<InputHandler<String, String>>()
+            .action_is_down("ui_select")
+            .unwrap_or(false);
+        if is_select_down {
+            *data.world.write_resource::<PlayerInput>() =
+                PlayerInput::default();
+        } else {
+            self.is_ignoring_select = false;
+        }
+    }
+
+    /// Run the fixed timestep physics ticks for this frame.
+    /// The tick rate is read from the settings every frame, so it can be hot-reloaded;
+    /// a replay is played back with the tick rate it was recorded with.
+    fn update_physics(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        let tick_rate = data
+            .world
+            .read_resource::<ReplayMode>()
+            .playback_tick_rate()
+            .unwrap_or_else(|| {
+                data.world.read_resource::<Settings>().physics.tick_rate
+            });
+        let delta_seconds = data.world.read_resource::<Time>().delta_seconds();
+        if let Some(physics) = self.physics.as_mut() {
+            physics.set_tick_rate(tick_rate);
+            physics.update(&mut data.world, delta_seconds);
+        }
+    }
+
     fn handle_keys(
         &self,
         data: &StateData<CustomGameData<DisplayConfig>>,
@@ -17,12 +108,114 @@ impl<'a, 'b> Ingame {
             None
         }
     }
+
+    /// Handle a request from a state, which ran on top of this one.
+    fn handle_request(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
+        let request = mem::replace(
+            &mut *data.world.write_resource::<IngameRequest>(),
+            IngameRequest::None,
+        );
+        match request {
+            IngameRequest::None => None,
+            IngameRequest::RestartLevel => {
+                if let Err(error) =
+                    self.level_manager.restart_level(&mut data.world)
+                {
+                    eprintln!("Error restarting level: {}", error);
+                }
+                None
+            }
+            IngameRequest::ReturnToMainMenu => Some(Trans::Pop),
+        }
+    }
+
+    /// Complete the level and build the next one, when a level exit was reached.
+    /// The next level is parsed first; if it can't be loaded, the run isn't completed,
+    /// and the game returns to the `MainMenu`, showing the error.
+    fn handle_level_exit(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
+        let level = data.world.write_resource::<LevelExitReached>().take()?;
+        match self.level_manager.parse_level(&level) {
+            Ok(map_loader) => {
+                self.complete_level(data);
+                self.level_manager.build_level(
+                    level,
+                    map_loader,
+                    &mut data.world,
+                );
+                save_game(&data.world);
+                None
+            }
+            Err(error) => {
+                let message =
+                    format!("Error loading level '{}':\n{}", level, error);
+                eprintln!("{}", message);
+                data.world.write_resource::<MainMenuError>().0 = Some(message);
+                Some(Trans::Pop)
+            }
+        }
+    }
+
+    /// Stop the level timer, and record the run if it's a personal best.
+    /// A personal best's recording is saved as the level's ghost.
+    fn complete_level(
+        &self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        let run = data.world.write_resource::<LevelTimer>().finish();
+        if let (Some(level), Some(run)) =
+            (self.level_manager.current_level(), run)
+        {
+            let is_best = data
+                .world
+                .write_resource::<SaveData>()
+                .record_run(level, run);
+            if is_best {
+                let ghosts = data.world.read_resource::<Ghosts>();
+                if let Err(e) = ghosts.recording.save(level) {
+                    eprintln!("{}", e);
+                }
+            }
+        }
+    }
 }
 
 impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
     for Ingame
 {
-    fn on_start(&mut self, data: StateData<CustomGameData<DisplayConfig>>) {
+    fn on_start(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
+        let tick_rate =
+            data.world.read_resource::<Settings>().physics.tick_rate;
+        self.physics = Some(PhysicsDispatcher::new(data.world, tick_rate));
+        // No menu buttons while playing
+        data.world.add_resource(UiButtons::default());
+        self.create_hud(&mut data);
+    }
+
+    fn on_resume(&mut self, data: StateData<CustomGameData<DisplayConfig>>) {
+        data.world.add_resource(UiButtons::default());
+        self.is_ignoring_select = true;
+        // Ghosts may have been turned on or off in the options
+        self.level_manager.apply_ghost_setting(data.world);
+    }
+
+    fn on_stop(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
+        // Tear down the level and HUD
+        self.level_manager.unload_level(data.world);
+        self.delete_hud(&mut data);
+
+        // Save collected items
+        save_game(&data.world);
+
+        // Save recorded input, if recording
+        if let Err(err) = data.world.read_resource::<ReplayMode>().save() {
+            eprintln!("{}", err);
+        }
     }
 
     fn handle_event(
@@ -43,9 +236,18 @@ impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
 
     fn update(
         &mut self,
-        data: StateData<CustomGameData<DisplayConfig>>,
+        mut data: StateData<CustomGameData<DisplayConfig>>,
     ) -> Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent> {
+        if let Some(trans) = self.handle_request(&mut data) {
+            return trans;
+        }
+
         data.data.update(&data.world, "ingame").unwrap();
+        self.consume_ui_select(&mut data);
+        self.update_physics(&mut data);
+        if let Some(trans) = self.handle_level_exit(&mut data) {
+            return trans;
+        }
         if let Some(trans) = self.handle_keys(&data) {
             return trans;
         }
@@ -0,0 +1,268 @@
+use amethyst::ecs::Join;
+
+use super::state_prelude::*;
+use super::{Ingame, MainMenuError, Options};
+use crate::game::{LevelManager, LevelManifest, MapLoadError};
+use crate::replay::ReplayMode;
+use crate::save_data::SaveData;
+use crate::ui_buttons::{UiAction, UiButtons};
+
+const LEVEL_SELECT_ID: &str = "main_menu_level_select";
+const STATUS_ID: &str = "main_menu_status";
+
+/// The first state after loading. Starts levels, and is returned to from the pause screen.
+pub struct MainMenu {
+    manifest:       LevelManifest,
+    /// Shown below the buttons, if the level manifest couldn't be loaded.
+    manifest_error: Option<String>,
+    /// Shown below the buttons, if `Ingame` returned because of an error.
+    ingame_error:   Option<String>,
+    selected_level: usize,
+    ui_entities:    Vec<Entity>,
+}
+
+impl<'a, 'b> MainMenu {
+    /// If the level manifest couldn't be loaded, the error is shown,
+    /// and there are no levels to start.
+    pub fn new(manifest: Result<LevelManifest, MapLoadError>) -> Self {
+        let (manifest, manifest_error) = match manifest {
+            Ok(manifest) => (manifest, None),
+            Err(error) => {
+                let message = format!("Error loading levels:\n{}", error);
+                eprintln!("{}", message);
+                (LevelManifest::default(), Some(message))
+            }
+        };
+        Self {
+            manifest:       manifest,
+            manifest_error: manifest_error,
+            ingame_error:   None,
+            selected_level: 0,
+            ui_entities:    Vec::new(),
+        }
+    }
+
+    fn create_ui(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        self.ui_entities
+            .push(data.world.exec(|mut creator: UiCreator| {
+                creator.create(resource("ui/main_menu.ron"), ())
+            }));
+        data.world.add_resource(UiButtons::new(vec![
+            ("main_menu_start", UiAction::Start),
+            (LEVEL_SELECT_ID, UiAction::LevelSelect),
+            ("main_menu_options", UiAction::Options),
+            ("main_menu_quit", UiAction::Quit),
+        ]));
+    }
+
+    fn delete_ui(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        data.world.delete_entities(&self.ui_entities).unwrap();
+        self.ui_entities.clear();
+    }
+
+    /// Show the selected level on the level select button,
+    /// and the level manifest's or the last level's error, if any.
+    fn update_ui(&self, data: &mut StateData<CustomGameData<DisplayConfig>>) {
+        let level_text = format!("LEVEL: {}", self.selected_level_name());
+        if let Some(message) =
+            self.manifest_error.as_ref().or(self.ingame_error.as_ref())
+        {
+            self.set_status(data, message);
+        }
+
+        let ui_transforms = data.world.read_storage::<UiTransform>();
+        let mut ui_texts = data.world.write_storage::<UiText>();
+        for (ui_transform, ui_text) in (&ui_transforms, &mut ui_texts).join() {
+            if ui_transform.id == LEVEL_SELECT_ID {
+                ui_text.text = level_text.clone();
+            }
+        }
+    }
+
+    /// Show an error message below the buttons.
+    fn show_error<T: ToString>(
+        &self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+        message: T,
+    ) {
+        let message = message.to_string();
+        eprintln!("{}", message);
+        self.set_status(data, message);
+    }
+
+    /// Set the text below the buttons.
+    fn set_status<T: ToString>(
+        &self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+        message: T,
+    ) {
+        let message = message.to_string();
+        let ui_transforms = data.world.read_storage::<UiTransform>();
+        let mut ui_texts = data.world.write_storage::<UiText>();
+        for (ui_transform, ui_text) in (&ui_transforms, &mut ui_texts).join() {
+            if ui_transform.id == STATUS_ID {
+                ui_text.text = message.clone();
+            }
+        }
+    }
+
+    fn selected_level_name(&self) -> &str {
+        self.manifest
+            .levels
+            .get(self.selected_level)
+            .map(|level| level.name.as_str())
+            .unwrap_or("")
+    }
+
+    /// Select the next unlocked level. The first level is always unlocked.
+    fn select_next_level(
+        &mut self,
+        data: &StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        let save_data = data.world.read_resource::<SaveData>();
+        let levels = &self.manifest.levels;
+        let selected_level = self.selected_level;
+        self.selected_level = (1..=levels.len())
+            .map(|offset| (selected_level + offset) % levels.len())
+            .find(|&index| {
+                index == 0 || save_data.is_level_unlocked(&levels[index].name)
+            })
+            .unwrap_or(0);
+    }
+
+    /// Load the given level, or the first one if none is given,
+    /// and push the `Ingame` state for it.
+    fn start_level(
+        &mut self,
+        level: &str,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
+        let mut level_manager = LevelManager::new(self.manifest.clone());
+        let result = if level.is_empty() {
+            level_manager.load_first_level(&mut data.world)
+        } else {
+            level_manager.start_level(level, &mut data.world)
+        };
+
+        match result {
+            Ok(()) => Some(Trans::Push(Box::new(Ingame::new(level_manager)))),
+            Err(error) => {
+                self.show_error(data, format!("Error loading map:\n{}", error));
+                None
+            }
+        }
+    }
+
+    /// Start a replay's recorded level, if a replay is about to be played back.
+    /// If it can't be loaded, the replay is dropped, and the menu is shown.
+    fn start_playback(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
+        let level = data
+            .world
+            .read_resource::<ReplayMode>()
+            .playback_level()
+            .map(String::from)?;
+        let trans = self.start_level(&level, data);
+        if trans.is_none() {
+            *data.world.write_resource::<ReplayMode>() = ReplayMode::Off;
+        }
+        trans
+    }
+
+    fn press_button(
+        &mut self,
+        action: UiAction,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
+        match action {
+            UiAction::Start => {
+                let level = self.selected_level_name().to_string();
+                self.start_level(&level, data)
+            }
+            UiAction::LevelSelect => {
+                self.select_next_level(data);
+                None
+            }
+            UiAction::Options => {
+                Some(Trans::Push(Box::new(Options::default())))
+            }
+            UiAction::Quit => Some(Trans::Quit),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
+    for MainMenu
+{
+    fn on_start(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
+        self.create_ui(&mut data);
+    }
+
+    fn on_stop(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
+        self.delete_ui(&mut data);
+    }
+
+    fn on_pause(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
+        // Hide the menu while a level or the options are shown
+        self.delete_ui(&mut data);
+    }
+
+    fn on_resume(
+        &mut self,
+        mut data: StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        self.ingame_error =
+            data.world.write_resource::<MainMenuError>().0.take();
+        self.create_ui(&mut data);
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<CustomGameData<DisplayConfig>>,
+        event: StateEvent,
+    ) -> Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent> {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) {
+                Trans::Quit
+            } else {
+                Trans::None
+            }
+        } else {
+            Trans::None
+        }
+    }
+
+    fn update(
+        &mut self,
+        mut data: StateData<CustomGameData<DisplayConfig>>,
+    ) -> Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent> {
+        data.data.update(&data.world, "main_menu").unwrap();
+
+        if data.world.input_manager().is_up("quit") {
+            return Trans::Quit;
+        }
+
+        self.update_ui(&mut data);
+
+        if let Some(trans) = self.start_playback(&mut data) {
+            return trans;
+        }
+
+        let pressed = data.world.write_resource::<UiButtons>().take_pressed();
+        if let Some(action) = pressed {
+            if let Some(trans) = self.press_button(action, &mut data) {
+                return trans;
+            }
+        }
+        Trans::None
+    }
+}
@@ -1,10 +1,16 @@
 mod ingame;
+mod main_menu;
+mod options;
 mod paused;
 // TODO: Temporary pub.
 pub mod startup;
 
 pub mod prelude {
     pub use super::Ingame;
+    pub use super::IngameRequest;
+    pub use super::MainMenu;
+    pub use super::MainMenuError;
+    pub use super::Options;
     pub use super::Paused;
     pub use super::Startup;
 }
@@ -48,5 +54,29 @@ mod state_prelude {
 }
 
 pub use ingame::Ingame;
+pub use main_menu::MainMenu;
+pub use options::Options;
 pub use paused::Paused;
 pub use startup::Startup;
+
+/// Set by states running on top of `Ingame`, like `Paused`.
+/// `Ingame` handles the request once it is running again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IngameRequest {
+    None,
+    /// Reload the current level from the start.
+    RestartLevel,
+    /// Tear down the level, and return to the `MainMenu`.
+    ReturnToMainMenu,
+}
+
+impl Default for IngameRequest {
+    fn default() -> Self {
+        IngameRequest::None
+    }
+}
+
+/// Set by `Ingame`, when it returns to the `MainMenu` because of an error.
+/// The `MainMenu` shows the error below its buttons.
+#[derive(Debug, Clone, Default)]
+pub struct MainMenuError(pub Option<String>);
@@ -0,0 +1,330 @@
+use amethyst::audio::AudioSink;
+use amethyst::ecs::Join;
+use amethyst::input::{Button, InputHandler};
+use amethyst::renderer::{ElementState, Event, KeyboardInput, WindowEvent};
+
+use super::state_prelude::*;
+use crate::key_bindings::{find_conflict, KeyBindingOverrides, REBINDABLE};
+use crate::save_data::SaveData;
+use crate::ui_buttons::{UiAction, UiButtons};
+
+/// How much the volume changes per button press.
+const VOLUME_STEP: f32 = 0.1;
+/// Id prefix of the key binding buttons, followed by their index in `REBINDABLE`.
+const BINDING_ID_PREFIX: &str = "options_binding_";
+const BINDINGS_STATUS_ID: &str = "options_bindings_status";
+
+/// Options screen, opened from the main menu or the pause menu.
+/// Changes the volume, toggles ghosts, and rebinds keys.
+/// A key binding is rebound by pressing its button, and then the new key.
+/// Keys already bound to any other binding are rejected, which also cancels rebinding;
+/// pressing any button cancels as well. Keys replaced by the new key are listed.
+pub struct Options {
+    ui_entities:      Vec<Entity>,
+    /// Index into `REBINDABLE` of the key binding waiting for a key press.
+    capturing:        Option<usize>,
+    /// After capturing a key, the menu's keys are ignored until all keys are released,
+    /// so the captured key doesn't also close the menu.
+    is_ignoring_keys: bool,
+    bindings_status:  String,
+}
+
+impl<'a, 'b> Options {
+    fn handle_keys(
+        &self,
+        data: &StateData<CustomGameData<DisplayConfig>>,
+    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
+        if self.is_ignoring_keys {
+            return None;
+        }
+        let input = data.world.input_manager();
+        if input.is_up("quit") || input.is_down("pause") {
+            Some(Trans::Pop)
+        } else {
+            None
+        }
+    }
+
+    fn press_button(
+        &mut self,
+        action: UiAction,
+        data: &StateData<CustomGameData<DisplayConfig>>,
+    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
+        match action {
+            UiAction::VolumeDown => {
+                change_volume(data, -VOLUME_STEP);
+                None
+            }
+            UiAction::VolumeUp => {
+                change_volume(data, VOLUME_STEP);
+                None
+            }
+            UiAction::ToggleGhosts => {
+                toggle_ghosts(data);
+                None
+            }
+            UiAction::Rebind(index) => {
+                self.capturing = Some(index);
+                self.bindings_status = String::new();
+                None
+            }
+            UiAction::Back => Some(Trans::Pop),
+            _ => None,
+        }
+    }
+
+    fn create_ui(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        self.ui_entities
+            .push(data.world.exec(|mut creator: UiCreator| {
+                creator.create(resource("ui/options.ron"), ())
+            }));
+        let binding_buttons = (0..REBINDABLE.len()).map(|index| {
+            (
+                format!("{}{}", BINDING_ID_PREFIX, index),
+                UiAction::Rebind(index),
+            )
+        });
+        data.world.add_resource(UiButtons::new(
+            vec![
+                ("options_volume_down".to_string(), UiAction::VolumeDown),
+                ("options_volume_up".to_string(), UiAction::VolumeUp),
+                ("options_ghosts".to_string(), UiAction::ToggleGhosts),
+            ]
+            .into_iter()
+            .chain(binding_buttons)
+            .chain(Some(("options_back".to_string(), UiAction::Back)))
+            .collect(),
+        ));
+    }
+
+    fn delete_ui(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        data.world.delete_entities(&self.ui_entities).unwrap();
+        self.ui_entities.clear();
+    }
+
+    /// Bind the captured key to the key binding being rebound,
+    /// unless the key is already bound to another one, and save the overrides.
+    fn capture_key(
+        &mut self,
+        data: &StateData<CustomGameData<DisplayConfig>>,
+        key: VirtualKeyCode,
+    ) {
+        let index = match self.capturing.take() {
+            Some(index) => index,
+            None => return,
+        };
+        self.is_ignoring_keys = true;
+
+        let id = REBINDABLE[index];
+        let button = Button::Key(key);
+        let mut input =
+            data.world.write_resource::<InputHandler<String, String>>();
+        if let Some(conflict) = find_conflict(&input.bindings, id, button) {
+            self.bindings_status = format!(
+                "{} IS ALREADY BOUND TO {}",
+                button_name(&button),
+                conflict
+            );
+            return;
+        }
+
+        let mut overrides = data.world.write_resource::<KeyBindingOverrides>();
+        let replaced = overrides.rebind(&mut input.bindings, id, button);
+        self.bindings_status = match overrides.save() {
+            Ok(()) if replaced.is_empty() => String::new(),
+            Ok(()) => format!(
+                "{} NO LONGER BOUND TO {}",
+                id.label(),
+                replaced
+                    .iter()
+                    .map(button_name)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Err(e) => {
+                eprintln!("{}", e);
+                "COULDN'T SAVE KEY BINDINGS".to_string()
+            }
+        };
+    }
+
+    /// Show the current volume, ghost setting and key bindings.
+    fn update_ui(&self, data: &mut StateData<CustomGameData<DisplayConfig>>) {
+        let volume_text = format!(
+            "VOLUME: {}%",
+            (data.world.read_resource::<AudioSink>().volume() * 100.0).round()
+        );
+        let ghosts_text =
+            if data.world.read_resource::<SaveData>().show_ghosts() {
+                "GHOSTS: ON"
+            } else {
+                "GHOSTS: OFF"
+            };
+        let binding_texts = REBINDABLE
+            .iter()
+            .enumerate()
+            .map(|(index, id)| {
+                if self.capturing == Some(index) {
+                    format!("{}: PRESS A KEY", id.label())
+                } else {
+                    let input = data.world.input();
+                    let keys = id
+                        .keys(&input.bindings)
+                        .iter()
+                        .map(button_name)
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    format!("{}: {}", id.label(), keys)
+                }
+            })
+            .collect::<Vec<String>>();
+
+        let ui_transforms = data.world.read_storage::<UiTransform>();
+        let mut ui_texts = data.world.write_storage::<UiText>();
+        for (ui_transform, ui_text) in (&ui_transforms, &mut ui_texts).join() {
+            match ui_transform.id.as_str() {
+                "options_volume" => ui_text.text = volume_text.clone(),
+                "options_ghosts" => ui_text.text = ghosts_text.to_string(),
+                BINDINGS_STATUS_ID => {
+                    ui_text.text = self.bindings_status.clone()
+                }
+                id if id.starts_with(BINDING_ID_PREFIX) => {
+                    if let Some(text) = id[BINDING_ID_PREFIX.len()..]
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| binding_texts.get(index))
+                    {
+                        ui_text.text = text.clone();
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
+    for Options
+{
+    fn on_start(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
+        self.create_ui(&mut data);
+    }
+
+    fn on_stop(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
+        self.delete_ui(&mut data);
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<CustomGameData<DisplayConfig>>,
+        event: StateEvent,
+    ) -> Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent> {
+        if let StateEvent::Window(event) = &event {
+            if let Some(key) = pressed_key(&event) {
+                self.capture_key(&data, key);
+            }
+            if is_close_requested(&event) {
+                Trans::Quit
+            } else {
+                Trans::None
+            }
+        } else {
+            Trans::None
+        }
+    }
+
+    fn update(
+        &mut self,
+        mut data: StateData<CustomGameData<DisplayConfig>>,
+    ) -> Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent> {
+        data.data.update(&data.world, "options").unwrap();
+        if self.is_ignoring_keys
+            && data.world.input().keys_that_are_down().next().is_none()
+        {
+            self.is_ignoring_keys = false;
+        }
+        if let Some(trans) = self.handle_keys(&data) {
+            return trans;
+        }
+        let pressed = data.world.write_resource::<UiButtons>().take_pressed();
+        if pressed.is_some() && self.capturing.is_some() {
+            // Pressing any button cancels capturing a key
+            self.capturing = None;
+        } else if let (Some(action), false) = (pressed, self.is_ignoring_keys) {
+            // Menu buttons don't react while the captured key is still held
+            if let Some(trans) = self.press_button(action, &data) {
+                return trans;
+            }
+        }
+        self.update_ui(&mut data);
+        Trans::None
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            ui_entities:      Vec::new(),
+            capturing:        None,
+            is_ignoring_keys: false,
+            bindings_status:  String::new(),
+        }
+    }
+}
+
+/// Change the volume, and save it as a settings override.
+fn change_volume(data: &StateData<CustomGameData<DisplayConfig>>, change: f32) {
+    let mut sink = data.world.write_resource::<AudioSink>();
+    let volume = (sink.volume() + change).max(0.0).min(1.0);
+    sink.set_volume(volume);
+
+    let mut save_data = data.world.write_resource::<SaveData>();
+    save_data.settings.volume = Some(volume);
+    if let Err(e) = save_data.save() {
+        eprintln!("{}", e);
+    }
+}
+
+/// Show or hide ghosts of best runs, and save it as a settings override.
+/// A running level creates or deletes its ghost, when it is resumed.
+fn toggle_ghosts(data: &StateData<CustomGameData<DisplayConfig>>) {
+    let mut save_data = data.world.write_resource::<SaveData>();
+    save_data.settings.show_ghosts = Some(!save_data.show_ghosts());
+    if let Err(e) = save_data.save() {
+        eprintln!("{}", e);
+    }
+}
+
+/// Returns the key, if the event is a key press.
+fn pressed_key(event: &Event) -> Option<VirtualKeyCode> {
+    match event {
+        Event::WindowEvent {
+            event:
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(key),
+                            ..
+                        },
+                    ..
+                },
+            ..
+        } => Some(*key),
+        _ => None,
+    }
+}
+
+/// Returns the button's name, as shown in the options.
+fn button_name(button: &Button) -> String {
+    match button {
+        Button::Key(key) => format!("{:?}", key),
+        button => format!("{:?}", button),
+    }
+}
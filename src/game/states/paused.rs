@@ -1,12 +1,16 @@
-use amethyst::ecs::{Entities, Join, ReadStorage, Write};
-use amethyst::shrev::{EventChannel, ReaderId};
-use amethyst::ui::{UiEvent, UiEventType, UiTransform};
+use amethyst::renderer::TextureData;
+use amethyst::ui::UiImage;
 
 use super::state_prelude::*;
+use super::{IngameRequest, Options};
+use crate::ui_buttons::{UiAction, UiButtons};
+
+/// Color of the overlay, which dims the frozen game behind the pause menu.
+const OVERLAY_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
 
 pub struct Paused {
-    ui_entities:  Vec<Entity>,
-    ui_reader_id: Option<ReaderId<UiEvent>>,
+    ui_entities:    Vec<Entity>,
+    overlay_entity: Option<Entity>,
 }
 
 impl<'a, 'b> Paused {
@@ -16,7 +20,7 @@ impl<'a, 'b> Paused {
     ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
         let input = data.world.input_manager();
         if input.is_up("quit") {
-            Some(Trans::Quit)
+            Some(self.request(data, IngameRequest::ReturnToMainMenu))
         } else if input.is_down("pause") {
             Some(Trans::Pop)
         } else {
@@ -24,16 +28,97 @@ impl<'a, 'b> Paused {
         }
     }
 
+    /// Pop back to `Ingame`, which handles the given request.
+    fn request(
+        &self,
+        data: &StateData<CustomGameData<DisplayConfig>>,
+        request: IngameRequest,
+    ) -> Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent> {
+        *data.world.write_resource::<IngameRequest>() = request;
+        Trans::Pop
+    }
+
+    fn press_button(
+        &self,
+        action: UiAction,
+        data: &StateData<CustomGameData<DisplayConfig>>,
+    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
+        match action {
+            UiAction::Resume => Some(Trans::Pop),
+            UiAction::RestartLevel => {
+                Some(self.request(data, IngameRequest::RestartLevel))
+            }
+            UiAction::Options => {
+                Some(Trans::Push(Box::new(Options::default())))
+            }
+            UiAction::QuitToMenu => {
+                Some(self.request(data, IngameRequest::ReturnToMainMenu))
+            }
+            _ => None,
+        }
+    }
+
+    fn create_overlay(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        let screen_size = data
+            .data
+            .custom
+            .clone()
+            .unwrap()
+            .dimensions
+            .unwrap_or((1200, 800));
+
+        let texture = data.world.read_resource::<Loader>().load_from_data(
+            TextureData::color(OVERLAY_COLOR),
+            (),
+            &data.world.read_resource::<AssetStorage<Texture>>(),
+        );
+
+        let transform = UiTransform::new(
+            "pause_overlay".to_string(),
+            AmethystAnchor::Middle,
+            0.0,                  // x
+            0.0,                  // y
+            0.0,                  // z
+            screen_size.0 as f32, // width
+            screen_size.1 as f32, // height
+            0,                    // tab-order
+        );
+
+        self.overlay_entity = Some(
+            data.world
+                .create_entity()
+                .with(transform)
+                .with(UiImage { texture: texture })
+                .build(),
+        );
+    }
+
+    fn delete_overlay(
+        &mut self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        if let Some(entity) = self.overlay_entity.take() {
+            data.world.delete_entity(entity).unwrap();
+        }
+    }
+
     fn create_ui(
         &mut self,
         data: &mut StateData<CustomGameData<DisplayConfig>>,
     ) {
         self.ui_entities
             .push(data.world.exec(|mut creator: UiCreator| {
-                let entity =
-                    creator.create(resource("ui/pause_button.ron"), ());
-                entity
+                creator.create(resource("ui/pause_menu.ron"), ())
             }));
+        data.world.add_resource(UiButtons::new(vec![
+            ("pause_resume", UiAction::Resume),
+            ("pause_restart", UiAction::RestartLevel),
+            ("pause_options", UiAction::Options),
+            ("pause_quit_to_menu", UiAction::QuitToMenu),
+        ]));
     }
 
     fn delete_ui(
@@ -43,43 +128,6 @@ impl<'a, 'b> Paused {
         data.world.delete_entities(&self.ui_entities).unwrap();
         self.ui_entities.clear();
     }
-
-    fn handle_ui_events(
-        &mut self,
-        data: &mut StateData<CustomGameData<DisplayConfig>>,
-    ) -> Option<Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>> {
-        data.world.exec(
-            |(entities, mut events, ui_transforms): (
-                Entities,
-                Write<EventChannel<UiEvent>>,
-                ReadStorage<UiTransform>,
-            )| {
-                if let Some(target_id) = (&entities, &ui_transforms)
-                    .join()
-                    .find_map(|(entity, ui_transform)| {
-                        if ui_transform.id == "pause_button" {
-                            Some(entity.id())
-                        } else {
-                            None
-                        }
-                    })
-                {
-                    let reader_id = self
-                        .ui_reader_id
-                        .get_or_insert_with(|| events.register_reader());
-                    for event in events.read(reader_id) {
-                        if event.event_type == UiEventType::ClickStop
-                            && event.target.id() == target_id
-                        {
-                            return Some(Trans::Pop);
-                        }
-                    }
-                }
-
-                None
-            },
-        )
-    }
 }
 
 impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
@@ -87,12 +135,26 @@ impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
 {
     fn on_start(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
         // Create paused UI
+        self.create_overlay(&mut data);
         self.create_ui(&mut data);
     }
 
     fn on_stop(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
         // Delete paused UI
         self.delete_ui(&mut data);
+        self.delete_overlay(&mut data);
+    }
+
+    fn on_pause(&mut self, mut data: StateData<CustomGameData<DisplayConfig>>) {
+        // Hide the menu while the options are shown, but keep the overlay
+        self.delete_ui(&mut data);
+    }
+
+    fn on_resume(
+        &mut self,
+        mut data: StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        self.create_ui(&mut data);
     }
 
     fn handle_event(
@@ -119,15 +181,11 @@ impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
         if let Some(trans) = self.handle_keys(&data) {
             return trans;
         }
-        Trans::None
-    }
-
-    fn fixed_update(
-        &mut self,
-        mut data: StateData<CustomGameData<DisplayConfig>>,
-    ) -> Trans<CustomGameData<'a, 'b, DisplayConfig>, StateEvent> {
-        if let Some(trans) = self.handle_ui_events(&mut data) {
-            return trans;
+        let pressed = data.world.write_resource::<UiButtons>().take_pressed();
+        if let Some(action) = pressed {
+            if let Some(trans) = self.press_button(action, &data) {
+                return trans;
+            }
         }
         Trans::None
     }
@@ -136,8 +194,8 @@ impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
 impl Default for Paused {
     fn default() -> Self {
         Self {
-            ui_entities:  Vec::new(),
-            ui_reader_id: None,
+            ui_entities:    Vec::new(),
+            overlay_entity: None,
         }
     }
 }
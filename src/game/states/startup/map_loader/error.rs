@@ -0,0 +1,136 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::components::ComponentError;
+
+/// Where in the map file an error occured.
+#[derive(Debug, Clone, Copy)]
+pub enum MapLocation {
+    /// The object at the given index in the map's `objects` array.
+    Object(usize),
+    /// The tile at the given index in the map's `tiles` array.
+    Tile(usize),
+}
+
+/// Errors that can occur while loading a map exported from Tiled.
+#[derive(Debug)]
+pub enum MapLoadError {
+    /// A map or tileset file couldn't be opened or read.
+    Io { path: String, error: io::Error },
+    /// A map or tileset file isn't valid JSON.
+    Json { path: String, error: json::Error },
+    /// The level manifest isn't valid RON, or doesn't match the `LevelManifest`.
+    Ron {
+        path:  String,
+        error: ron::de::Error,
+    },
+    /// A required field is missing, or has the wrong type.
+    MissingField {
+        location: MapLocation,
+        key:      String,
+    },
+    /// A property has an unexpected type.
+    InvalidProperty {
+        location: MapLocation,
+        key:      String,
+        expected: &'static str,
+    },
+    /// A property string couldn't be parsed as a vector (`"x, y"`).
+    InvalidVector {
+        location: MapLocation,
+        key:      String,
+        value:    String,
+    },
+    /// An entry of a `components` property is unknown or invalid.
+    Component {
+        location: MapLocation,
+        error:    ComponentError,
+    },
+    /// An object's type isn't registered in the `ObjectRegistry`.
+    UnknownObjectType {
+        location:    MapLocation,
+        object_type: String,
+    },
+    /// A tile references a tileset, which isn't in the tileset manifest.
+    UnknownTileset {
+        location: MapLocation,
+        tileset:  String,
+    },
+    /// A tileset manifest entry is missing a field, or its value is invalid.
+    InvalidTileset { tileset: String, key: String },
+    /// A level with the given name isn't in the level manifest.
+    UnknownLevel { level: String },
+    /// The level manifest doesn't have any levels.
+    NoLevels,
+}
+
+impl fmt::Display for MapLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapLocation::Object(index) => write!(f, "object #{}", index),
+            MapLocation::Tile(index) => write!(f, "tile #{}", index),
+        }
+    }
+}
+
+impl fmt::Display for MapLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapLoadError::Io { path, error } => {
+                write!(f, "Couldn't read file '{}': {}", path, error)
+            }
+            MapLoadError::Json { path, error } => {
+                write!(f, "Couldn't parse JSON file '{}': {}", path, error)
+            }
+            MapLoadError::Ron { path, error } => {
+                write!(f, "Couldn't parse RON file '{}': {}", path, error)
+            }
+            MapLoadError::MissingField { location, key } => {
+                write!(f, "{}: missing field '{}'", location, key)
+            }
+            MapLoadError::InvalidProperty {
+                location,
+                key,
+                expected,
+            } => write!(
+                f,
+                "{}: property '{}' should be {}",
+                location, key, expected
+            ),
+            MapLoadError::InvalidVector {
+                location,
+                key,
+                value,
+            } => write!(
+                f,
+                "{}: property '{}' is not a vector \"x, y\": '{}'",
+                location, key, value
+            ),
+            MapLoadError::Component { location, error } => {
+                write!(f, "{}: {}", location, error)
+            }
+            MapLoadError::UnknownObjectType {
+                location,
+                object_type,
+            } => {
+                write!(f, "{}: unknown object type '{}'", location, object_type)
+            }
+            MapLoadError::UnknownTileset { location, tileset } => {
+                write!(f, "{}: unknown tileset '{}'", location, tileset)
+            }
+            MapLoadError::InvalidTileset { tileset, key } => write!(
+                f,
+                "tileset '{}': missing or invalid field '{}'",
+                tileset, key
+            ),
+            MapLoadError::UnknownLevel { level } => {
+                write!(f, "unknown level '{}'", level)
+            }
+            MapLoadError::NoLevels => write!(f, "level manifest is empty"),
+        }
+    }
+}
+
+impl Error for MapLoadError {
+}
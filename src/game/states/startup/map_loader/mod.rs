@@ -0,0 +1,563 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use amethyst::ecs::world::Index;
+use amethyst::ecs::EntityBuilder;
+use deathframe::geo::Vector;
+use json::JsonValue;
+
+use super::super::state_prelude::*;
+use super::constants::*;
+use crate::components::prelude::*;
+use crate::components::{ComponentRegistry, MapComponent};
+use crate::map_bounds::{CameraBounds, MapBounds};
+
+pub use error::{MapLoadError, MapLocation};
+pub use objects::{
+    BuildContext,
+    BuildPhase,
+    MapObject,
+    ObjectRegistry,
+    ParseObject,
+};
+
+mod error;
+mod objects;
+
+struct SpriteData {
+    pub spritesheet_path: String,
+    pub sprite_id:        usize,
+}
+
+struct TextureData {}
+
+enum Graphic {
+    Sprite(SpriteData),
+    Texture(TextureData),
+}
+
+struct TilesetData {
+    pub tile_size: Vector,
+}
+
+/// An object's or tile's position, size, properties and parsed components,
+/// as loaded from the map.
+pub struct EntityData {
+    pub pos:        Vector,
+    pub size:       Vector,
+    pub properties: JsonValue,
+    graphic:        Option<Graphic>,
+    pub components: Vec<Box<dyn MapComponent>>,
+    /// The points of a polyline object, relative to its position.
+    /// Empty for other objects and tiles.
+    pub polyline:   Vec<Vector>,
+}
+
+pub struct MapLoader {
+    camera_id:     Option<Index>,
+    player_id:     Option<Index>,
+    objects:       Vec<Box<dyn MapObject>>,
+    tilesets:      HashMap<String, TilesetData>,
+    tiles_data:    Vec<EntityData>,
+    entities:      Vec<Entity>,
+    load_graphics: bool,
+    components:    ComponentRegistry,
+    object_types:  ObjectRegistry,
+}
+
+impl MapLoader {
+    pub fn new() -> Self {
+        Self {
+            camera_id:     None,
+            player_id:     None,
+            objects:       Vec::new(),
+            tilesets:      HashMap::new(),
+            tiles_data:    Vec::new(),
+            entities:      Vec::new(),
+            load_graphics: true,
+            components:    ComponentRegistry::default(),
+            object_types:  ObjectRegistry::default(),
+        }
+    }
+
+    /// Set the registry of components, which can be used in the maps' `components` properties.
+    /// Defaults to `ComponentRegistry::default()`, with all of the game's components.
+    pub fn component_registry(mut self, components: ComponentRegistry) -> Self {
+        self.components = components;
+        self
+    }
+
+    /// Set if sprites and textures should be loaded and added to the built entities.
+    /// Defaults to `true`; disable for headless simulations, which have no renderer.
+    pub fn load_graphics(mut self, load_graphics: bool) -> Self {
+        self.load_graphics = load_graphics;
+        self
+    }
+
+    /// Set the registry of object types, which can be used in the maps.
+    /// Defaults to `ObjectRegistry::default()`, with all of the game's object types.
+    pub fn object_registry(mut self, object_types: ObjectRegistry) -> Self {
+        self.object_types = object_types;
+        self
+    }
+
+    /// Returns `true` if everything has finished loading and building properly.
+    pub fn is_finished(&self) -> bool {
+        self.player_id.is_some() && self.camera_id.is_some()
+    }
+
+    /// Start loading the map data from the given map filename.
+    /// The tileset manifest is read from the same path, with the extension `.ts.json`
+    /// (`map.json` -> `map.ts.json`), as written by the Tiled export script.
+    /// Returns an error if a file can't be read, or if it contains invalid data.
+    pub fn load_map<T>(&mut self, filename: T) -> Result<(), MapLoadError>
+    where
+        T: ToString,
+    {
+        let filename = filename.to_string();
+        let tilesets_filename =
+            format!("{}.ts.json", filename.trim_end_matches(".json"));
+        let json = load_json(resource(&filename))?;
+        let tilesets_json = load_json(resource(&tilesets_filename))?;
+
+        // TILESETS
+        self.load_tilesets(&tilesets_json)?;
+
+        // OBJECTS
+        self.load_objects(&json["objects"])?;
+
+        // TILES
+        self.load_tiles(&json["tiles"])?;
+
+        Ok(())
+    }
+
+    /// Builds the loaded data into the given `World`.
+    pub fn build(&mut self, world: &mut World) {
+        let mut context = BuildContext {
+            load_graphics: self.load_graphics,
+            camera_id:     None,
+            player_id:     None,
+        };
+
+        // Without tiles, the previous map's bounds must not be kept.
+        // A `CameraBounds` object replaces the camera bounds, when it is built
+        let map_bounds_opt = self.map_bounds();
+        match map_bounds_opt {
+            Some(bounds) => world.add_resource(bounds),
+            None => {
+                world.res.remove::<MapBounds>();
+            }
+        }
+        world.add_resource(CameraBounds {
+            bounds: map_bounds_opt,
+        });
+
+        self.build_objects(world, &mut context, BuildPhase::BeforeCamera);
+        self.player_id = context.player_id;
+        self.build_camera(world);
+        context.camera_id = self.camera_id;
+        self.build_tiles(world);
+        self.build_objects(world, &mut context, BuildPhase::AfterCamera);
+    }
+
+    /// Deletes all entities created by `build` (tiles, parallax backgrounds, player, camera, ...).
+    /// Loaded assets (sprite sheets, textures) are kept, so they can be reused by the next map.
+    pub fn unload(&mut self, world: &mut World) {
+        // Some entities may have been deleted already (collected collectibles)
+        let entities = self
+            .entities
+            .iter()
+            .filter(|entity| world.is_alive(**entity))
+            .cloned()
+            .collect::<Vec<Entity>>();
+        world
+            .delete_entities(&entities)
+            .expect("Should delete map entities");
+        self.entities.clear();
+        self.player_id = None;
+        self.camera_id = None;
+    }
+
+    fn load_tilesets(&mut self, json: &JsonValue) -> Result<(), MapLoadError> {
+        for (tileset_name, tileset_data) in json.entries() {
+            let invalid = |key: &str| MapLoadError::InvalidTileset {
+                tileset: tileset_name.to_string(),
+                key:     key.to_string(),
+            };
+            let image_filename = tileset_data["image_filename"]
+                .as_str()
+                .ok_or_else(|| invalid("image_filename"))?;
+            let tile_size = (
+                tileset_data["tile_size"]["w"]
+                    .as_f32()
+                    .filter(|w| *w > 0.0)
+                    .ok_or_else(|| invalid("tile_size.w"))?,
+                tileset_data["tile_size"]["h"]
+                    .as_f32()
+                    .filter(|h| *h > 0.0)
+                    .ok_or_else(|| invalid("tile_size.h"))?,
+            );
+
+            // Tiles reference their tileset by the image's filename, without extension.
+            let image_name = Path::new(image_filename)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| invalid("image_filename"))?;
+            let image_path = resource(format!("textures/{}", image_filename));
+            let is_file = fs::metadata(&image_path)
+                .map(|metadata| metadata.is_file())
+                .map_err(|error| MapLoadError::Io {
+                    path: image_path.clone(),
+                    error,
+                })?;
+            if !is_file {
+                return Err(MapLoadError::Io {
+                    path:  image_path,
+                    error: io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "tileset image is not a file",
+                    ),
+                });
+            }
+
+            self.tilesets.insert(image_name.to_string(), TilesetData {
+                tile_size: tile_size.into(),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn load_objects(&mut self, json: &JsonValue) -> Result<(), MapLoadError> {
+        for (index, object_data) in json.members().enumerate() {
+            let location = MapLocation::Object(index);
+            let obj_type = object_data["type"]
+                .as_str()
+                .ok_or_else(|| missing_field(location, "type"))?;
+            let entity_data = EntityData {
+                pos:        (
+                    get_f32(&object_data["pos"], location, "pos.x", "x")?,
+                    get_f32(&object_data["pos"], location, "pos.y", "y")?,
+                )
+                    .into(),
+                size:       (
+                    get_f32(&object_data["size"], location, "size.w", "w")?,
+                    get_f32(&object_data["size"], location, "size.h", "h")?,
+                )
+                    .into(),
+                properties: object_data["properties"].clone(),
+                graphic:    None,
+                components: self
+                    .load_components(location, &object_data["properties"])?,
+                polyline:   load_polyline(location, &object_data["polyline"])?,
+            };
+
+            // Objects of unknown types are skipped, so a map can still be played,
+            // while it has objects of types, which aren't implemented yet
+            match self.object_types.parse(obj_type, location, entity_data) {
+                Ok(object) => self.objects.push(object),
+                Err(error @ MapLoadError::UnknownObjectType { .. }) => {
+                    eprintln!("Skipping map object: {}", error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn load_tiles(&mut self, json: &JsonValue) -> Result<(), MapLoadError> {
+        for (index, tile_data) in json.members().enumerate() {
+            let location = MapLocation::Tile(index);
+            let id = tile_data["id"]
+                .as_usize()
+                .ok_or_else(|| missing_field(location, "id"))?;
+            let pos = (
+                get_f32(&tile_data["pos"], location, "pos.x", "x")?,
+                get_f32(&tile_data["pos"], location, "pos.y", "y")?,
+            );
+            let tileset_name = tile_data["ts"]
+                .as_str()
+                .ok_or_else(|| missing_field(location, "ts"))?;
+            let properties = &tile_data["properties"];
+
+            let components = self.load_components(location, properties)?;
+
+            let tile_size = self
+                .tilesets
+                .get(tileset_name)
+                .map(|tileset| tileset.tile_size)
+                .ok_or_else(|| MapLoadError::UnknownTileset {
+                    location,
+                    tileset: tileset_name.to_string(),
+                })?;
+            let spritesheet_path =
+                resource(format!("textures/{}.png", tileset_name));
+
+            self.tiles_data.push(EntityData {
+                pos:        pos.into(),
+                size:       tile_size,
+                properties: properties.clone(),
+                graphic:    Some(Graphic::Sprite(SpriteData {
+                    spritesheet_path: spritesheet_path,
+                    sprite_id:        id,
+                })),
+                components: components,
+                polyline:   Vec::new(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Parses the `components` property, a list of component strings
+    /// (see `ComponentRegistry`). Unknown or invalid components are errors.
+    fn load_components(
+        &self,
+        location: MapLocation,
+        properties: &JsonValue,
+    ) -> Result<Vec<Box<dyn MapComponent>>, MapLoadError> {
+        properties["components"]
+            .members()
+            .map(|component| {
+                let component = component.as_str().ok_or_else(|| {
+                    MapLoadError::InvalidProperty {
+                        location,
+                        key: "components".to_string(),
+                        expected: "a list of strings",
+                    }
+                })?;
+                self.components
+                    .parse(component, properties)
+                    .map_err(|error| MapLoadError::Component {
+                        location,
+                        error,
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the area covered by all tiles, or `None` if there are no tiles.
+    fn map_bounds(&self) -> Option<MapBounds> {
+        self.tiles_data.iter().fold(None, |bounds_opt, tile| {
+            let half_size = (tile.size.0 * 0.5, tile.size.1 * 0.5);
+            let tile_bounds = MapBounds {
+                left:   tile.pos.0 - half_size.0,
+                right:  tile.pos.0 + half_size.0,
+                bottom: tile.pos.1 - half_size.1,
+                top:    tile.pos.1 + half_size.1,
+            };
+            Some(match bounds_opt {
+                None => tile_bounds,
+                Some(bounds) => MapBounds {
+                    left:   bounds.left.min(tile_bounds.left),
+                    right:  bounds.right.max(tile_bounds.right),
+                    bottom: bounds.bottom.min(tile_bounds.bottom),
+                    top:    bounds.top.max(tile_bounds.top),
+                },
+            })
+        })
+    }
+
+    /// Builds the objects of the given phase.
+    fn build_objects(
+        &mut self,
+        world: &mut World,
+        context: &mut BuildContext,
+        phase: BuildPhase,
+    ) {
+        for object in &self.objects {
+            if object.phase() == phase {
+                if let Some(entity) = object.build(world, context) {
+                    self.entities.push(entity);
+                }
+            }
+        }
+    }
+
+    fn build_camera(&mut self, world: &mut World) {
+        let settings = world.settings();
+
+        let mut transform = Transform::default();
+        transform.set_z(CAMERA_Z);
+
+        let mut camera = Camera::new()
+            .base_speed({ settings.camera.base_speed })
+            .deadzone({ settings.camera.deadzone });
+
+        if let Some(player_id) = self.player_id {
+            camera = camera.follow(player_id);
+        }
+
+        let entity = world
+            .create_entity()
+            .with(AmethystCamera::from(Projection::orthographic(
+                0.0,                    // Left
+                settings.camera.size.0, // Right
+                0.0,                    // Bottom (!)
+                settings.camera.size.1, // Top    (!)
+            )))
+            .with(camera.build())
+            .with(transform)
+            .with(Size::from(settings.camera.size))
+            .with(InnerSize(Size::from(settings.camera.inner_size)))
+            .with(Velocity::default())
+            .with(Collision::new())
+            .build();
+
+        self.camera_id = Some(entity.id());
+        self.entities.push(entity);
+    }
+
+    fn build_tiles(&mut self, world: &mut World) {
+        for EntityData {
+            pos,
+            size,
+            properties,
+            graphic,
+            components,
+            ..
+        } in &self.tiles_data
+        {
+            let mut transform = Transform::default();
+            transform.set_xyz(
+                pos.0,
+                pos.1,
+                properties[PROPERTY_Z_KEY].as_f32().unwrap_or(FOREGROUND_Z),
+            );
+
+            let sprite_render_opt =
+                if let Some(Graphic::Sprite(sprite_data)) = graphic {
+                    if self.load_graphics {
+                        let spritesheet_handle = world
+                            .write_resource::<SpriteSheetHandles>()
+                            .get_or_load(&sprite_data.spritesheet_path, &world);
+                        Some(SpriteRender {
+                            sprite_sheet:  spritesheet_handle,
+                            sprite_number: sprite_data.sprite_id,
+                        })
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+
+            let mut entity = world
+                .create_entity()
+                .with(transform)
+                .with(Size::from(*size))
+                .with(ScaleOnce)
+                .with(Transparent);
+
+            if let Some(sprite_render) = sprite_render_opt {
+                entity = entity.with(sprite_render);
+            }
+
+            entity = add_components(entity, components);
+
+            self.entities.push(entity.build());
+        }
+    }
+}
+
+/// Adds the given parsed components to the entity.
+fn add_components<'a>(
+    mut entity: EntityBuilder<'a>,
+    components: &[Box<dyn MapComponent>],
+) -> EntityBuilder<'a> {
+    for component in components {
+        entity = component.add_to(entity);
+    }
+    entity
+}
+
+/// Reads the optional list of polyline points `[{ "x": 0.0, "y": 0.0 }, ...]`.
+fn load_polyline(
+    location: MapLocation,
+    json: &JsonValue,
+) -> Result<Vec<Vector>, MapLoadError> {
+    json.members()
+        .map(|point| {
+            Ok((
+                get_f32(point, location, "polyline.x", "x")?,
+                get_f32(point, location, "polyline.y", "y")?,
+            )
+                .into())
+        })
+        .collect()
+}
+
+/// Reads and parses the JSON file at the given path.
+fn load_json(path: String) -> Result<JsonValue, MapLoadError> {
+    let json_raw = read_file(&path).map_err(|error| MapLoadError::Io {
+        path: path.clone(),
+        error,
+    })?;
+    json::parse(&json_raw).map_err(|error| MapLoadError::Json { path, error })
+}
+
+fn missing_field<T>(location: MapLocation, key: T) -> MapLoadError
+where
+    T: ToString,
+{
+    MapLoadError::MissingField {
+        location,
+        key: key.to_string(),
+    }
+}
+
+/// Returns the number at `json[field]`, or a `MissingField` error for `key`.
+fn get_f32(
+    json: &JsonValue,
+    location: MapLocation,
+    key: &str,
+    field: &str,
+) -> Result<f32, MapLoadError> {
+    json[field]
+        .as_f32()
+        .ok_or_else(|| missing_field(location, key))
+}
+
+/// Parses the optional vector property `"x, y"` at `properties[key]`.
+fn get_vector(
+    properties: &JsonValue,
+    location: MapLocation,
+    key: &str,
+) -> Result<Option<Vector>, MapLoadError> {
+    match &properties[key] {
+        JsonValue::Null => Ok(None),
+        val => {
+            let invalid = || MapLoadError::InvalidVector {
+                location,
+                key: key.to_string(),
+                value: val.dump(),
+            };
+            val.as_str()
+                .and_then(parse_string_to_vector)
+                .map(Some)
+                .ok_or_else(invalid)
+        }
+    }
+}
+
+/// Parses a string like `"1.0, 2.0"` to a `Vector`.
+/// Returns `None` if it doesn't have exactly two numeric fields.
+fn parse_string_to_vector<T>(string: T) -> Option<Vector>
+where
+    T: ToString,
+{
+    let vec = string
+        .to_string()
+        .split(",")
+        .map(|s| s.trim().parse::<f32>().ok())
+        .collect::<Option<Vec<f32>>>()?;
+    if vec.len() == 2 {
+        Some((vec[0], vec[1]).into())
+    } else {
+        None
+    }
+}
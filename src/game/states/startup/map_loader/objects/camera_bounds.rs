@@ -0,0 +1,35 @@
+use super::*;
+use crate::map_bounds::{CameraBounds, MapBounds};
+
+/// The area the camera's view is kept inside of, instead of the area covered by the tiles.
+/// Builds no entity; it sets the `CameraBounds` resource.
+pub struct CameraBoundsObject {
+    entity: EntityData,
+}
+
+impl CameraBoundsObject {
+    pub fn parse(
+        _location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        Ok(Box::new(Self { entity }))
+    }
+}
+
+impl MapObject for CameraBoundsObject {
+    fn build(
+        &self,
+        world: &mut World,
+        _context: &mut BuildContext,
+    ) -> Option<Entity> {
+        // The export script writes an object's top-left corner, with y pointing up
+        let (x, y) = (self.entity.pos.0, self.entity.pos.1);
+        world.write_resource::<CameraBounds>().bounds = Some(MapBounds {
+            left:   x,
+            right:  x + self.entity.size.0,
+            bottom: y - self.entity.size.1,
+            top:    y,
+        });
+        None
+    }
+}
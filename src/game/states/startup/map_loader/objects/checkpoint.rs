@@ -0,0 +1,34 @@
+use super::*;
+
+/// When the player enters it, they respawn at its position after dying.
+pub struct CheckpointObject {
+    entity: EntityData,
+}
+
+impl CheckpointObject {
+    pub fn parse(
+        _location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        Ok(Box::new(Self { entity }))
+    }
+}
+
+impl MapObject for CheckpointObject {
+    fn build(
+        &self,
+        world: &mut World,
+        _context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let entity = world
+            .create_entity()
+            .with(object_transform(&self.entity, FOREGROUND_Z))
+            .with(Size::from(self.entity.size))
+            .with(Collision::new())
+            .with(Checkpoint {
+                id: format!("{},{}", self.entity.pos.0, self.entity.pos.1),
+                ..Checkpoint::default()
+            });
+        Some(add_components(entity, &self.entity.components).build())
+    }
+}
@@ -0,0 +1,95 @@
+use amethyst::renderer::Rgba;
+
+use super::super::missing_field;
+use super::*;
+use crate::score::Score;
+
+/// Tint of collectibles, which were already collected in a previous run of the level.
+const GHOST_TINT: Rgba = Rgba(1.0, 1.0, 1.0, 0.3);
+
+/// An item (coin, gem, ...), counted to the `Score` when the player touches it.
+///
+/// Properties:
+/// - `id`: unique in the level; an item is only counted once per level.
+/// - `value`: score added when collected (optional, defaults to `1`).
+/// - `sound`: name of the sound played when collected (optional).
+/// - `spritesheet` and `sprite_id`: the sprite to draw (optional, see `ObjectSprite`).
+pub struct CollectibleObject {
+    entity: EntityData,
+    id:     String,
+    value:  u32,
+    sound:  Option<String>,
+    sprite: Option<ObjectSprite>,
+}
+
+impl CollectibleObject {
+    pub fn parse(
+        location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        let properties = &entity.properties;
+        // NOTE: The export script turns numeric ids into numbers
+        let id = properties["id"]
+            .as_str()
+            .map(ToString::to_string)
+            .or_else(|| properties["id"].as_i64().map(|id| id.to_string()))
+            .ok_or_else(|| missing_field(location, "properties.id"))?;
+        let value = match &properties["value"] {
+            JsonValue::Null => 1,
+            val => {
+                val.as_u32().ok_or_else(|| MapLoadError::InvalidProperty {
+                    location,
+                    key: "value".to_string(),
+                    expected: "a positive integer",
+                })?
+            }
+        };
+        let sound = properties["sound"].as_str().map(ToString::to_string);
+        let sprite = ObjectSprite::parse(location, properties)?;
+
+        Ok(Box::new(Self {
+            entity,
+            id,
+            value,
+            sound,
+            sprite,
+        }))
+    }
+}
+
+impl MapObject for CollectibleObject {
+    fn build(
+        &self,
+        world: &mut World,
+        context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let is_ghost = world.read_resource::<Score>().is_collected(&self.id);
+        let sprite_render_opt = self
+            .sprite
+            .as_ref()
+            .and_then(|sprite| sprite.sprite_render(world, context));
+
+        let mut entity = world
+            .create_entity()
+            .with(object_transform(&self.entity, FOREGROUND_Z))
+            .with(Size::from(self.entity.size))
+            .with(ScaleOnce)
+            .with(Transparent)
+            .with(Collision::new())
+            .with(Collectible {
+                id:       self.id.clone(),
+                value:    self.value,
+                sound:    self.sound.clone(),
+                is_ghost: is_ghost,
+            });
+
+        if let Some(sprite_render) = sprite_render_opt {
+            entity = entity.with(sprite_render);
+        }
+        if is_ghost {
+            entity = entity.with(GHOST_TINT);
+        }
+
+        Some(add_components(entity, &self.entity.components).build())
+    }
+}
@@ -0,0 +1,61 @@
+use super::moving_platform::WaypointPath;
+use super::*;
+
+/// Kills the player, when they touch it (see `Hazard`),
+/// and patrols along its waypoints, if it has any.
+///
+/// Properties:
+/// - `waypoints`, `speed` and `mode`: the path to patrol (optional, see `WaypointPath`).
+/// - `spritesheet` and `sprite_id`: the sprite to draw (optional, see `ObjectSprite`).
+pub struct EnemyObject {
+    entity: EntityData,
+    path:   Option<WaypointPath>,
+    sprite: Option<ObjectSprite>,
+}
+
+impl EnemyObject {
+    pub fn parse(
+        location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        let path = WaypointPath::parse(location, &entity)?;
+        let sprite = ObjectSprite::parse(location, &entity.properties)?;
+
+        Ok(Box::new(Self {
+            entity,
+            path,
+            sprite,
+        }))
+    }
+}
+
+impl MapObject for EnemyObject {
+    fn build(
+        &self,
+        world: &mut World,
+        context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let sprite_render_opt = self
+            .sprite
+            .as_ref()
+            .and_then(|sprite| sprite.sprite_render(world, context));
+
+        let mut entity = world
+            .create_entity()
+            .with(object_transform(&self.entity, FOREGROUND_Z))
+            .with(Size::from(self.entity.size))
+            .with(ScaleOnce)
+            .with(Transparent)
+            .with(Collision::new())
+            .with(Hazard);
+
+        if let Some(path) = &self.path {
+            entity = entity.with(path.moving_platform());
+        }
+        if let Some(sprite_render) = sprite_render_opt {
+            entity = entity.with(sprite_render);
+        }
+
+        Some(add_components(entity, &self.entity.components).build())
+    }
+}
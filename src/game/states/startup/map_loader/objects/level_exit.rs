@@ -0,0 +1,38 @@
+use super::super::missing_field;
+use super::*;
+
+/// When the player enters it, the level from the `level` property is loaded.
+pub struct LevelExitObject {
+    entity: EntityData,
+    level:  String,
+}
+
+impl LevelExitObject {
+    pub fn parse(
+        location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        let level = entity.properties["level"]
+            .as_str()
+            .ok_or_else(|| missing_field(location, "properties.level"))?
+            .to_string();
+
+        Ok(Box::new(Self { entity, level }))
+    }
+}
+
+impl MapObject for LevelExitObject {
+    fn build(
+        &self,
+        world: &mut World,
+        _context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let entity = world
+            .create_entity()
+            .with(object_transform(&self.entity, FOREGROUND_Z))
+            .with(Size::from(self.entity.size))
+            .with(Collision::new())
+            .with(LevelExit::new(&self.level));
+        Some(add_components(entity, &self.entity.components).build())
+    }
+}
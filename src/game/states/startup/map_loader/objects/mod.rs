@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use amethyst::ecs::world::Index;
+use json::JsonValue;
+
+use super::super::super::state_prelude::*;
+use super::super::constants::*;
+use super::{add_components, EntityData, MapLoadError, MapLocation};
+use crate::components::prelude::*;
+
+pub use camera_bounds::CameraBoundsObject;
+pub use checkpoint::CheckpointObject;
+pub use collectible::CollectibleObject;
+pub use enemy::EnemyObject;
+pub use level_exit::LevelExitObject;
+pub use moving_platform::MovingPlatformObject;
+pub use parallax::ParallaxObject;
+pub use player::PlayerObject;
+pub use spike::SpikeObject;
+pub use trigger::TriggerObject;
+
+mod camera_bounds;
+mod checkpoint;
+mod collectible;
+mod enemy;
+mod level_exit;
+mod moving_platform;
+mod parallax;
+mod player;
+mod spike;
+mod trigger;
+
+/// When an object is built, relative to the camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BuildPhase {
+    /// Built before the camera, so the camera can follow it (the player).
+    BeforeCamera,
+    /// Built after the camera and the tiles, so it can reference the camera.
+    AfterCamera,
+}
+
+/// Map-wide data, which objects can use and set while they are built.
+pub struct BuildContext {
+    pub load_graphics: bool,
+    pub camera_id:     Option<Index>,
+    pub player_id:     Option<Index>,
+}
+
+/// An object from the map, parsed by its type's `ParseObject` function,
+/// and ready to be built into the world.
+pub trait MapObject: Send + Sync {
+    fn phase(&self) -> BuildPhase {
+        BuildPhase::AfterCamera
+    }
+
+    /// Build the object's entity, and return it.
+    /// Returns `None` if it can't be built.
+    fn build(
+        &self,
+        world: &mut World,
+        context: &mut BuildContext,
+    ) -> Option<Entity>;
+}
+
+/// Parses an object's position, size and properties to a `MapObject`.
+pub type ParseObject =
+    fn(MapLocation, EntityData) -> Result<Box<dyn MapObject>, MapLoadError>;
+
+/// Maps object types, as set in Tiled, to functions parsing the objects.
+pub struct ObjectRegistry {
+    types: HashMap<&'static str, ParseObject>,
+}
+
+impl ObjectRegistry {
+    /// Create an empty registry, without any object types.
+    pub fn new() -> Self {
+        Self {
+            types: HashMap::new(),
+        }
+    }
+
+    /// Register an object type with the given name.
+    /// A previously registered type with the same name is replaced.
+    pub fn register(mut self, name: &'static str, parse: ParseObject) -> Self {
+        self.types.insert(name, parse);
+        self
+    }
+
+    /// Parse an object of the given type.
+    /// Returns an `UnknownObjectType` error, if the type isn't registered.
+    pub fn parse(
+        &self,
+        object_type: &str,
+        location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        let parse = self.types.get(object_type).ok_or_else(|| {
+            MapLoadError::UnknownObjectType {
+                location,
+                object_type: object_type.to_string(),
+            }
+        })?;
+        parse(location, entity)
+    }
+}
+
+/// Registers all object types, which can be used in maps.
+impl Default for ObjectRegistry {
+    fn default() -> Self {
+        Self::new()
+            .register("Player", PlayerObject::parse)
+            .register("Parallax", ParallaxObject::parse)
+            .register("LevelExit", LevelExitObject::parse)
+            .register("Checkpoint", CheckpointObject::parse)
+            .register("Collectible", CollectibleObject::parse)
+            .register("MovingPlatform", MovingPlatformObject::parse)
+            .register("Trigger", TriggerObject::parse)
+            .register("Spike", SpikeObject::parse)
+            .register("Enemy", EnemyObject::parse)
+            .register("CameraBounds", CameraBoundsObject::parse)
+    }
+}
+
+/// Returns a `Transform` at the object's position,
+/// with the z value from its `z` property, or the given default.
+fn object_transform(entity: &EntityData, default_z: f32) -> Transform {
+    let mut transform = Transform::default();
+    transform.set_xyz(
+        entity.pos.0,
+        entity.pos.1,
+        entity.properties[PROPERTY_Z_KEY]
+            .as_f32()
+            .unwrap_or(default_z),
+    );
+    transform
+}
+
+/// An optional sprite for objects, from the `spritesheet` property
+/// (an image filename in `textures/`) and the `sprite_id` property (defaults to `0`).
+pub struct ObjectSprite {
+    spritesheet: String,
+    sprite_id:   usize,
+}
+
+impl ObjectSprite {
+    /// Returns `None`, if the object has no `spritesheet` property.
+    fn parse(
+        location: MapLocation,
+        properties: &JsonValue,
+    ) -> Result<Option<Self>, MapLoadError> {
+        match &properties["spritesheet"] {
+            JsonValue::Null => Ok(None),
+            val => Ok(Some(Self {
+                spritesheet: val
+                    .as_str()
+                    .ok_or_else(|| MapLoadError::InvalidProperty {
+                        location,
+                        key: "spritesheet".to_string(),
+                        expected: "an image filename",
+                    })?
+                    .to_string(),
+                sprite_id:   properties["sprite_id"].as_usize().unwrap_or(0),
+            })),
+        }
+    }
+
+    /// Loads the sprite sheet, and returns the `SpriteRender`.
+    /// Returns `None`, if graphics aren't loaded.
+    fn sprite_render(
+        &self,
+        world: &World,
+        context: &BuildContext,
+    ) -> Option<SpriteRender> {
+        if !context.load_graphics {
+            return None;
+        }
+        let spritesheet_handle =
+            world.write_resource::<SpriteSheetHandles>().get_or_load(
+                resource(format!("textures/{}", self.spritesheet)),
+                &world,
+            );
+        Some(SpriteRender {
+            sprite_sheet:  spritesheet_handle,
+            sprite_number: self.sprite_id,
+        })
+    }
+}
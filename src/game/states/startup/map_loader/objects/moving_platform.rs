@@ -0,0 +1,146 @@
+use deathframe::geo::Vector;
+use json::JsonValue;
+
+use super::super::parse_string_to_vector;
+use super::*;
+
+const DEFAULT_SPEED: f32 = 50.0;
+
+/// A path along waypoints, at a constant speed, for `MovingPlatform` components.
+///
+/// Properties:
+/// - `waypoints`: offsets from the object's position, like `"0, 0; 64, 0"`.
+///   Not needed if the object is a polyline; the polyline's points are used instead.
+/// - `speed`: units per second (optional).
+/// - `mode`: `"ping_pong"` (default) or `"loop"` (optional).
+pub struct WaypointPath {
+    waypoints: Vec<Vector>,
+    speed:     f32,
+    mode:      MovingPlatformMode,
+}
+
+impl WaypointPath {
+    /// Returns `None`, if the object is neither a polyline,
+    /// nor has a `waypoints` property.
+    pub fn parse(
+        location: MapLocation,
+        entity: &EntityData,
+    ) -> Result<Option<Self>, MapLoadError> {
+        let properties = &entity.properties;
+        let invalid =
+            |key: &str, expected: &'static str| MapLoadError::InvalidProperty {
+                location,
+                key: key.to_string(),
+                expected,
+            };
+
+        let offsets = if !entity.polyline.is_empty() {
+            entity.polyline.clone()
+        } else if properties["waypoints"].is_null() {
+            return Ok(None);
+        } else {
+            properties["waypoints"]
+                .as_str()
+                .ok_or_else(|| {
+                    invalid("waypoints", "a list of vectors \"x, y; x, y\"")
+                })?
+                .split(";")
+                .map(parse_string_to_vector)
+                .collect::<Option<Vec<Vector>>>()
+                .ok_or_else(|| {
+                    invalid("waypoints", "a list of vectors \"x, y; x, y\"")
+                })?
+        };
+        let waypoints = offsets
+            .iter()
+            .map(|offset| (entity.pos.0 + offset.0, entity.pos.1 + offset.1))
+            .collect();
+
+        let speed = match &properties["speed"] {
+            JsonValue::Null => DEFAULT_SPEED,
+            val => val.as_f32().ok_or_else(|| invalid("speed", "a number"))?,
+        };
+
+        let mode = match properties["mode"].as_str() {
+            None | Some("ping_pong") => MovingPlatformMode::PingPong,
+            Some("loop") => MovingPlatformMode::Loop,
+            Some(_) => {
+                return Err(invalid("mode", "\"ping_pong\" or \"loop\""));
+            }
+        };
+
+        Ok(Some(Self {
+            waypoints,
+            speed,
+            mode,
+        }))
+    }
+
+    /// Returns a `MovingPlatform` component, moving along this path.
+    pub fn moving_platform(&self) -> MovingPlatform {
+        MovingPlatform::new(self.waypoints.clone(), self.speed, self.mode)
+    }
+}
+
+/// A solid platform, moving along its waypoints.
+///
+/// Properties:
+/// - `waypoints`, `speed` and `mode`: the path to move along (see `WaypointPath`).
+/// - `spritesheet` and `sprite_id`: the sprite to draw (optional, see `ObjectSprite`).
+pub struct MovingPlatformObject {
+    entity: EntityData,
+    path:   WaypointPath,
+    sprite: Option<ObjectSprite>,
+}
+
+impl MovingPlatformObject {
+    pub fn parse(
+        location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        let path =
+            WaypointPath::parse(location, &entity)?.ok_or_else(|| {
+                MapLoadError::InvalidProperty {
+                    location,
+                    key: "waypoints".to_string(),
+                    expected: "a list of vectors \"x, y; x, y\"",
+                }
+            })?;
+        let sprite = ObjectSprite::parse(location, &entity.properties)?;
+
+        Ok(Box::new(Self {
+            entity,
+            path,
+            sprite,
+        }))
+    }
+}
+
+impl MapObject for MovingPlatformObject {
+    fn build(
+        &self,
+        world: &mut World,
+        context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let sprite_render_opt = self
+            .sprite
+            .as_ref()
+            .and_then(|sprite| sprite.sprite_render(world, context));
+
+        let mut entity = world
+            .create_entity()
+            .with(object_transform(&self.entity, FOREGROUND_Z))
+            .with(Size::from(self.entity.size))
+            .with(ScaleOnce)
+            .with(Transparent)
+            .with(Solid)
+            .with(Collision::new())
+            .with(self.path.moving_platform());
+
+        if let Some(sprite_render) = sprite_render_opt {
+            entity = entity.with(sprite_render);
+        }
+
+        Some(add_components(entity, &self.entity.components).build())
+    }
+}
@@ -0,0 +1,94 @@
+use deathframe::geo::{Anchor, Vector};
+use json::JsonValue;
+
+use super::super::get_vector;
+use super::*;
+
+/// A parallax background image, following the camera.
+pub struct ParallaxObject {
+    entity:     EntityData,
+    image:      Option<String>,
+    speed_mult: Option<Vector>,
+    offset:     Option<Vector>,
+}
+
+impl ParallaxObject {
+    /// Reads the `Parallax` specific properties of an object.
+    pub fn parse(
+        location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        let properties = &entity.properties;
+        let image = match &properties["image"] {
+            JsonValue::Null => None,
+            val => Some(
+                val.as_str()
+                    .ok_or_else(|| MapLoadError::InvalidProperty {
+                        location,
+                        key: "image".to_string(),
+                        expected: "an image filename",
+                    })?
+                    .to_string(),
+            ),
+        };
+        let speed_mult = get_vector(properties, location, "speed_mult")?;
+        let offset = get_vector(properties, location, "offset")?;
+
+        Ok(Box::new(Self {
+            entity,
+            image,
+            speed_mult,
+            offset,
+        }))
+    }
+}
+
+impl MapObject for ParallaxObject {
+    fn build(
+        &self,
+        world: &mut World,
+        context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let camera_id = context.camera_id?;
+
+        // Load bg image texture
+        let texture_handle_opt =
+            self.image.as_ref().filter(|_| context.load_graphics).map(
+                |bg_filename| {
+                    let filepath =
+                        format!("{}/{}", resource("textures/bg"), bg_filename);
+                    world
+                        .write_resource::<TextureHandles>()
+                        .get_or_load(filepath, &world)
+                },
+            );
+
+        // Create entity
+        let mut entity = world.create_entity();
+        let mut parallax = Parallax::new()
+            .follow(camera_id)
+            .follow_anchor(Anchor::BottomLeft);
+
+        if let Some(speed_mult) = self.speed_mult {
+            parallax = parallax.speed_mult(speed_mult);
+        }
+        if let Some(offset) = self.offset {
+            parallax = parallax.offset(offset);
+        }
+        if let Some(texture_handle) = texture_handle_opt {
+            entity = entity.with(texture_handle);
+        }
+
+        // Add transform and size to entity
+        // NOTE: Draw parallax backgrounds behind foreground
+        entity = entity
+            .with(object_transform(&self.entity, BACKGROUND_Z))
+            .with(Size::from(self.entity.size))
+            .with(Velocity::default())
+            .with(ScaleOnce)
+            .with(Transparent)
+            .with(parallax.build());
+
+        Some(add_components(entity, &self.entity.components).build())
+    }
+}
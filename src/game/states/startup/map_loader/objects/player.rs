@@ -0,0 +1,92 @@
+use super::*;
+use crate::components::PlayerAnimations;
+
+/// The player. Built before the camera, which follows it.
+pub struct PlayerObject {
+    entity: EntityData,
+}
+
+impl PlayerObject {
+    pub fn parse(
+        _location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        Ok(Box::new(Self { entity }))
+    }
+}
+
+impl MapObject for PlayerObject {
+    fn phase(&self) -> BuildPhase {
+        BuildPhase::BeforeCamera
+    }
+
+    fn build(
+        &self,
+        world: &mut World,
+        context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let settings = world.settings();
+
+        // NOTE: Draw player above foreground elements
+        let transform = object_transform(&self.entity, FORE_FOREGROUND_Z);
+        let size = Size::from(self.entity.size);
+
+        let spritesheet_path = resource("textures/spritesheet_player.png");
+        let graphics_opt = if context.load_graphics {
+            let spritesheet_handle = world
+                .write_resource::<SpriteSheetHandles>()
+                .get_or_load(spritesheet_path, &world);
+            let animation_opt = match PlayerAnimations::load() {
+                Ok(animations) => Some(PlayerAnimation::new(animations)),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    None
+                }
+            };
+            Some((
+                SpriteRender {
+                    sprite_sheet:  spritesheet_handle,
+                    sprite_number: 0,
+                },
+                animation_opt,
+            ))
+        } else {
+            None
+        };
+
+        let mut player = world
+            .create_entity()
+            .with(
+                Player::new()
+                    .acceleration(settings.player.acceleration)
+                    .run_acceleration(settings.player.run_acceleration)
+                    .max_velocity(settings.player.max_velocity)
+                    .run_max_velocity(settings.player.run_max_velocity)
+                    .spawn_point(self.entity.pos)
+                    .build(),
+            )
+            .with(transform)
+            .with(Transparent)
+            .with(Velocity::default())
+            .with(MaxVelocity::from(settings.player.max_velocity))
+            .with(DecreaseVelocity::from(settings.player.decr_velocity))
+            .with(size)
+            .with(ScaleOnce)
+            .with(Gravity::from(settings.player.gravity))
+            .with(Solid)
+            .with(Collision::new())
+            .with(CheckCollision)
+            .with(Push);
+
+        if let Some((sprite_render, animation_opt)) = graphics_opt {
+            player = player.with(sprite_render);
+            if let Some(animation) = animation_opt {
+                player = player.with(animation);
+            }
+        }
+
+        let player = add_components(player, &self.entity.components).build();
+        context.player_id = Some(player.id());
+        Some(player)
+    }
+}
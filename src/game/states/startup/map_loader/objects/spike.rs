@@ -0,0 +1,48 @@
+use super::*;
+
+/// Kills the player, when they touch it (see `Hazard`).
+///
+/// Properties:
+/// - `spritesheet` and `sprite_id`: the sprite to draw (optional, see `ObjectSprite`).
+pub struct SpikeObject {
+    entity: EntityData,
+    sprite: Option<ObjectSprite>,
+}
+
+impl SpikeObject {
+    pub fn parse(
+        location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        let sprite = ObjectSprite::parse(location, &entity.properties)?;
+        Ok(Box::new(Self { entity, sprite }))
+    }
+}
+
+impl MapObject for SpikeObject {
+    fn build(
+        &self,
+        world: &mut World,
+        context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let sprite_render_opt = self
+            .sprite
+            .as_ref()
+            .and_then(|sprite| sprite.sprite_render(world, context));
+
+        let mut entity = world
+            .create_entity()
+            .with(object_transform(&self.entity, FOREGROUND_Z))
+            .with(Size::from(self.entity.size))
+            .with(ScaleOnce)
+            .with(Transparent)
+            .with(Collision::new())
+            .with(Hazard);
+
+        if let Some(sprite_render) = sprite_render_opt {
+            entity = entity.with(sprite_render);
+        }
+
+        Some(add_components(entity, &self.entity.components).build())
+    }
+}
@@ -0,0 +1,31 @@
+use super::*;
+
+/// An invisible area, which other entities can collide with.
+/// What it does is decided by its `components` property.
+pub struct TriggerObject {
+    entity: EntityData,
+}
+
+impl TriggerObject {
+    pub fn parse(
+        _location: MapLocation,
+        entity: EntityData,
+    ) -> Result<Box<dyn MapObject>, MapLoadError> {
+        Ok(Box::new(Self { entity }))
+    }
+}
+
+impl MapObject for TriggerObject {
+    fn build(
+        &self,
+        world: &mut World,
+        _context: &mut BuildContext,
+    ) -> Option<Entity> {
+        let entity = world
+            .create_entity()
+            .with(object_transform(&self.entity, FOREGROUND_Z))
+            .with(Size::from(self.entity.size))
+            .with(Collision::new());
+        Some(add_components(entity, &self.entity.components).build())
+    }
+}
@@ -1,11 +1,22 @@
+use std::fs;
+
 use amethyst::audio::AudioSink;
+use amethyst::input::InputHandler;
+use amethyst::renderer::Rgba;
 
 use super::state_prelude::*;
-use super::Ingame;
+use super::{IngameRequest, MainMenu, MainMenuError};
 use crate::components::prelude::*;
-use map_loader::MapLoader;
+use crate::game::load_level_manifest;
+use crate::ghost::Ghosts;
+use crate::key_bindings::KeyBindingOverrides;
+use crate::level_exit::LevelExitReached;
+use crate::level_timer::LevelTimer;
+use crate::save_data::SaveData;
+use crate::score::Score;
+use crate::ui_buttons::UiButtons;
 
-mod map_loader;
+pub mod map_loader;
 
 mod constants {
     pub const CAMERA_Z: f32 = 10.0;
@@ -13,21 +24,19 @@ mod constants {
     pub const FOREGROUND_Z: f32 = 0.0;
     pub const FORE_FOREGROUND_Z: f32 = 0.5;
     pub const PROPERTY_Z_KEY: &str = "z";
-    // pub const TILE_SIZE: (f32, f32) = (32.0, 32.0); // TODO: Read this data from tileset JSON file
-    pub const TILE_SIZE: (f32, f32) = (16.0, 16.0); // TODO: Read this data from tileset JSON file
-
 }
 
+/// Volume, if it wasn't changed in the options.
+const DEFAULT_VOLUME: f32 = 0.5;
+
 pub struct Startup {
     loading_entity: Option<Entity>,
-    map_loader:     MapLoader,
 }
 
 impl Startup {
     pub fn new() -> Self {
         Self {
             loading_entity: None,
-            map_loader:     MapLoader::new(),
         }
     }
 
@@ -43,7 +52,6 @@ impl Startup {
         spritesheet_handles.has_finished_loading_all(&data.world)
             && texture_handles.has_finished_loading_all(&data.world)
             && audio_handles.has_finished_loading_all(&data.world)
-            && self.map_loader.is_finished()
     }
 
     /// Register components (can be removed once systems using the components are in place)
@@ -51,6 +59,14 @@ impl Startup {
         world.register::<Transparent>();
         world.register::<Solid>();
         world.register::<Collision>();
+        world.register::<MovingPlatform>();
+        world.register::<Hazard>();
+        world.register::<Checkpoint>();
+        world.register::<Collectible>();
+        world.register::<Rgba>();
+        world.register::<OneWay>();
+        world.register::<Ghost>();
+        world.register::<PlayerAnimation>();
     }
 
     fn initialize_loading_text(
@@ -98,18 +114,55 @@ impl Startup {
         );
     }
 
+    /// Load the save file, and restore the collected items and settings from it.
+    fn initialize_save_data(
+        &self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        let save_data = SaveData::load();
+
+        let mut score = Score::default();
+        score.levels = save_data.tallies.clone();
+        data.world.add_resource(score);
+
+        let volume = save_data.settings.volume.unwrap_or(DEFAULT_VOLUME);
+        data.world.write_resource::<AudioSink>().set_volume(volume);
+
+        data.world.add_resource(save_data);
+    }
+
+    fn initialize_key_bindings(
+        &self,
+        data: &mut StateData<CustomGameData<DisplayConfig>>,
+    ) {
+        let overrides = KeyBindingOverrides::load();
+        overrides.apply(
+            &mut data
+                .world
+                .write_resource::<InputHandler<String, String>>()
+                .bindings,
+        );
+        data.world.add_resource(overrides);
+    }
+
     fn initialize_audio(
         &self,
         data: &mut StateData<CustomGameData<DisplayConfig>>,
     ) {
-        {
-            let mut sink = data.world.write_resource::<AudioSink>();
-            sink.set_volume(0.5);
-        }
         let mut audio_handles = AudioHandles::default();
         audio_handles.load(resource("audio/music/music.ogg"), &mut data.world);
-        audio_handles
-            .load(resource("audio/sfx/player_jump.ogg"), &mut data.world);
+        // Load all sound effects, so they can be played by name (filename without extension)
+        match fs::read_dir(resource("audio/sfx")) {
+            Ok(entries) => {
+                for entry in entries.filter_map(|entry| entry.ok()) {
+                    audio_handles.load(
+                        entry.path().to_string_lossy().into_owned(),
+                        &mut data.world,
+                    );
+                }
+            }
+            Err(e) => eprintln!("Couldn't read sound effects directory: {}", e),
+        }
 
         data.world.add_resource(audio_handles);
     }
@@ -128,6 +181,12 @@ impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
         // Audio
         self.initialize_audio(&mut data);
 
+        // Save data, with volume override
+        self.initialize_save_data(&mut data);
+
+        // Key bindings, with user overrides
+        self.initialize_key_bindings(&mut data);
+
         // Update manually once, so the "Loading" text is displayed
         data.data.update(&data.world, "startup").unwrap();
 
@@ -136,12 +195,15 @@ impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
         data.world.add_resource(TextureHandles::default());
 
         // Settings RON
-        let settings = load_settings();
+        let settings = load_settings().expect("Couldn't load settings");
         data.world.add_resource(settings);
 
-        // Load map
-        self.map_loader.load_map("map.json");
-        self.map_loader.build(&mut data);
+        data.world.add_resource(IngameRequest::default());
+        data.world.add_resource(MainMenuError::default());
+        data.world.add_resource(UiButtons::default());
+        data.world.add_resource(LevelTimer::default());
+        data.world.add_resource(Ghosts::default());
+        data.world.add_resource(LevelExitReached::default());
     }
 
     fn handle_event(
@@ -167,8 +229,8 @@ impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
         data.data.update(&data.world, "startup").unwrap();
 
         if self.is_finished_loading(&data) {
-            // Create new Ingame state first
-            let ingame = Box::new(Ingame);
+            // Create new MainMenu state first
+            let main_menu = Box::new(MainMenu::new(load_level_manifest()));
             // Remove loading text
             if let Some(entity) = self.loading_entity {
                 data.world
@@ -176,22 +238,13 @@ impl<'a, 'b> State<CustomGameData<'a, 'b, DisplayConfig>, StateEvent>
                     .expect("Should delete loading text entity");
             }
             // Switch state
-            return Trans::Switch(ingame);
+            return Trans::Switch(main_menu);
         }
 
         Trans::None
     }
 }
 
-fn load_settings() -> Settings {
-    let settings_raw = read_file(resource("config/settings.ron"))
-        .expect("Couldn't read settings.ron file");
-    ron::Value::from_str(&settings_raw)
-        .unwrap()
-        .into_rust()
-        .unwrap()
-}
-
 /// `UiTransform::new` wrapper
 fn new_ui_transform<T: ToString>(
     name: T,
@@ -0,0 +1,64 @@
+//! Ghost replays of a level's best run.
+
+use crate::resource_helpers::*;
+
+/// The player's position and sprite during a single physics tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhostFrame {
+    pub pos:     (f32, f32),
+    pub sprite:  usize,
+    /// If the sprite is flipped horizontally, facing left.
+    #[serde(default)]
+    pub flipped: bool,
+}
+
+/// The player's frames of a run, from the level timer's start until the level exit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GhostRun {
+    pub frames: Vec<GhostFrame>,
+}
+
+impl GhostRun {
+    /// Returns the path to the level's ghost file, in the user data directory.
+    pub fn path(level: &str) -> String {
+        user_data_file(format!("ghosts/{}.ron", level))
+    }
+
+    /// Load the level's ghost, if it has one.
+    pub fn load(level: &str) -> Option<Self> {
+        let path = Self::path(level);
+        let ghost_raw = read_file(&path).ok()?;
+        match ron::de::from_str(&ghost_raw) {
+            Ok(ghost) => Some(ghost),
+            Err(e) => {
+                eprintln!("Couldn't parse ghost '{}': {}", path, e);
+                None
+            }
+        }
+    }
+
+    /// Save the ghost as the level's best run.
+    pub fn save(&self, level: &str) -> Result<(), String> {
+        let path = Self::path(level);
+        let ghost_raw = ron::ser::to_string(self)
+            .map_err(|e| format!("Couldn't serialize ghost: {}", e))?;
+        write_file_atomic(&path, ghost_raw)
+            .map_err(|e| format!("Couldn't write ghost '{}': {}", path, e))
+    }
+
+    /// Returns the frame at the given level timer tick.
+    /// Before the first and after the last frame, the first and last frames are returned.
+    pub fn frame_at(&self, tick: u64) -> Option<&GhostFrame> {
+        let index = (tick.saturating_sub(1) as usize)
+            .min(self.frames.len().saturating_sub(1));
+        self.frames.get(index)
+    }
+}
+
+/// Resource with the current run's recording, and the best run to play back.
+/// Reset by the `LevelManager`, when a level is loaded.
+#[derive(Debug, Clone, Default)]
+pub struct Ghosts {
+    pub recording: GhostRun,
+    pub best:      Option<GhostRun>,
+}
@@ -0,0 +1,239 @@
+//! Key bindings changed in the options, saved to a user override file,
+//! which is layered over the default bindings from `resources/config/bindings.ron`.
+
+use std::collections::HashMap;
+
+use amethyst::input::{Axis, Bindings, Button};
+use ron::ser::PrettyConfig;
+
+use crate::resource_helpers::*;
+
+/// Filename of the override file, in the user data directory.
+const OVERRIDES_FILENAME: &str = "bindings.ron";
+
+/// A key binding, which can be changed in the options.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BindingId {
+    Action(&'static str),
+    /// The positive direction of an emulated axis.
+    AxisPos(&'static str),
+    /// The negative direction of an emulated axis.
+    AxisNeg(&'static str),
+}
+
+/// The key bindings, which can be changed in the options, in the order they are listed.
+pub const REBINDABLE: [BindingId; 6] = [
+    BindingId::Action("quit"),
+    BindingId::Action("pause"),
+    BindingId::Action("player_jump"),
+    BindingId::Action("player_run"),
+    BindingId::AxisPos("player_x"),
+    BindingId::AxisNeg("player_x"),
+];
+
+impl BindingId {
+    /// Returns the name shown in the options.
+    pub fn label(&self) -> String {
+        match self {
+            BindingId::Action(name) => name.to_string(),
+            BindingId::AxisPos(name) => axis_pos_label(name),
+            BindingId::AxisNeg(name) => axis_neg_label(name),
+        }
+    }
+
+    fn is_action(&self, action: &str) -> bool {
+        match self {
+            BindingId::Action(name) => *name == action,
+            _ => false,
+        }
+    }
+
+    fn is_axis_pos(&self, axis: &str) -> bool {
+        match self {
+            BindingId::AxisPos(name) => *name == axis,
+            _ => false,
+        }
+    }
+
+    fn is_axis_neg(&self, axis: &str) -> bool {
+        match self {
+            BindingId::AxisNeg(name) => *name == axis,
+            _ => false,
+        }
+    }
+
+    /// Returns the keyboard keys bound to this.
+    /// Controller bindings can't be changed, so they aren't included.
+    pub fn keys(&self, bindings: &Bindings<String, String>) -> Vec<Button> {
+        match self {
+            BindingId::Action(name) => bindings
+                .action_bindings(*name)
+                .filter(|combo| is_key_combo(combo))
+                .flat_map(|combo| combo.iter().cloned())
+                .collect(),
+            BindingId::AxisPos(name) => match bindings.axis(*name) {
+                Some(Axis::Emulated { pos, .. }) => vec![*pos],
+                _ => Vec::new(),
+            },
+            BindingId::AxisNeg(name) => match bindings.axis(*name) {
+                Some(Axis::Emulated { neg, .. }) => vec![*neg],
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+/// Returns the label of the other binding, which the key is already bound to, if any.
+/// All bindings are checked, not only the ones which can be changed in the options,
+/// so a key can't be bound to an action and also be used by the menus (`ui_*`).
+pub fn find_conflict(
+    bindings: &Bindings<String, String>,
+    id: BindingId,
+    key: Button,
+) -> Option<String> {
+    let action_conflict = bindings
+        .actions()
+        .filter(|action| !id.is_action(action))
+        .find(|action| {
+            bindings
+                .action_bindings(action.as_str())
+                .any(|combo| combo.contains(&key))
+        })
+        .map(|action| action.to_string());
+    if action_conflict.is_some() {
+        return action_conflict;
+    }
+
+    for axis in bindings.axes() {
+        if let Some(Axis::Emulated { pos, neg }) = bindings.axis(axis.as_str())
+        {
+            if *pos == key && !id.is_axis_pos(axis) {
+                return Some(axis_pos_label(axis));
+            }
+            if *neg == key && !id.is_axis_neg(axis) {
+                return Some(axis_neg_label(axis));
+            }
+        }
+    }
+    None
+}
+
+fn axis_pos_label(axis: &str) -> String {
+    format!("{} +", axis)
+}
+
+fn axis_neg_label(axis: &str) -> String {
+    format!("{} -", axis)
+}
+
+/// The key bindings changed in the options.
+/// Actions are bound to a single key; the default keyboard bindings are replaced,
+/// controller bindings are kept.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindingOverrides {
+    pub actions: HashMap<String, Button>,
+    /// The emulated axes' keys, as `(pos, neg)`.
+    pub axes:    HashMap<String, (Button, Button)>,
+}
+
+impl KeyBindingOverrides {
+    /// Returns the path to the override file, in the user data directory.
+    pub fn path() -> String {
+        user_data_file(OVERRIDES_FILENAME)
+    }
+
+    /// Load the override file.
+    /// If there is none yet, or it can't be parsed, no bindings are overridden.
+    pub fn load() -> Self {
+        let path = Self::path();
+        let overrides_raw = match read_file(&path) {
+            Ok(overrides_raw) => overrides_raw,
+            Err(_) => return Self::default(),
+        };
+        ron::de::from_str(&overrides_raw).unwrap_or_else(|e| {
+            eprintln!("Couldn't parse key bindings '{}': {}", path, e);
+            Self::default()
+        })
+    }
+
+    /// Write the override file atomically, see `write_file_atomic`.
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        let overrides_raw =
+            ron::ser::to_string_pretty(self, PrettyConfig::default()).map_err(
+                |e| format!("Couldn't serialize key bindings: {}", e),
+            )?;
+        write_file_atomic(&path, overrides_raw).map_err(|e| {
+            format!("Couldn't write key bindings '{}': {}", path, e)
+        })
+    }
+
+    /// Bind the key, and apply it to the given bindings.
+    /// Returns the keys, which were bound to it before, and aren't anymore;
+    /// an action's other keyboard keys are replaced as well.
+    pub fn rebind(
+        &mut self,
+        bindings: &mut Bindings<String, String>,
+        id: BindingId,
+        key: Button,
+    ) -> Vec<Button> {
+        let previous_keys = id.keys(bindings);
+        match id {
+            BindingId::Action(name) => {
+                self.actions.insert(name.to_string(), key);
+            }
+            BindingId::AxisPos(name) | BindingId::AxisNeg(name) => {
+                let pos = BindingId::AxisPos(name).keys(bindings).pop();
+                let neg = BindingId::AxisNeg(name).keys(bindings).pop();
+                let keys = match (id, pos, neg) {
+                    (BindingId::AxisPos(_), _, Some(neg)) => (key, neg),
+                    (BindingId::AxisNeg(_), Some(pos), _) => (pos, key),
+                    _ => (key, key),
+                };
+                self.axes.insert(name.to_string(), keys);
+            }
+        }
+        self.apply(bindings);
+        previous_keys
+            .into_iter()
+            .filter(|previous_key| *previous_key != key)
+            .collect()
+    }
+
+    /// Layer the overridden key bindings over the given bindings.
+    pub fn apply(&self, bindings: &mut Bindings<String, String>) {
+        for (action, key) in self.actions.iter() {
+            let key_combos = bindings
+                .action_bindings(action.as_str())
+                .filter(|combo| is_key_combo(combo))
+                .map(|combo| combo.to_vec())
+                .collect::<Vec<Vec<Button>>>();
+            for combo in key_combos {
+                bindings.remove_action_binding(action.as_str(), &combo);
+            }
+            if let Err(e) =
+                bindings.insert_action_binding(action.clone(), vec![*key])
+            {
+                eprintln!("Couldn't bind '{}': {:?}", action, e);
+            }
+        }
+        for (axis, (pos, neg)) in self.axes.iter() {
+            bindings.remove_axis(axis.as_str());
+            if let Err(e) = bindings.insert_axis(axis.clone(), Axis::Emulated {
+                pos: *pos,
+                neg: *neg,
+            }) {
+                eprintln!("Couldn't bind '{}': {:?}", axis, e);
+            }
+        }
+    }
+}
+
+/// Returns `true` if the combo only consists of keyboard keys.
+fn is_key_combo(combo: &[Button]) -> bool {
+    combo.iter().all(|button| match button {
+        Button::Key(_) | Button::ScanCode(_) => true,
+        _ => false,
+    })
+}
@@ -0,0 +1,34 @@
+//! The level exit, which the player has reached during the physics ticks.
+
+/// Resource, set by the `LevelExitSystem` when the player enters a `LevelExit`.
+/// A single frame can run several physics ticks, so the exit is detected in every tick,
+/// and taken by the `Ingame` state after the frame's ticks have run.
+/// Reset by the `LevelManager`, when a level is loaded.
+#[derive(Debug, Clone, Default)]
+pub struct LevelExitReached {
+    /// The name of the level to switch to.
+    level: Option<String>,
+}
+
+impl LevelExitReached {
+    /// Set the level to switch to.
+    /// If an exit was already reached, and wasn't taken yet, it is kept.
+    pub fn reach<T>(&mut self, level: T)
+    where
+        T: ToString,
+    {
+        if self.level.is_none() {
+            self.level = Some(level.to_string());
+        }
+    }
+
+    /// Returns the name of the level to switch to, if an exit was reached.
+    pub fn level(&self) -> Option<&str> {
+        self.level.as_ref().map(String::as_str)
+    }
+
+    /// Returns the name of the level to switch to, and clears it.
+    pub fn take(&mut self) -> Option<String> {
+        self.level.take()
+    }
+}
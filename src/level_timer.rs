@@ -0,0 +1,192 @@
+//! Speedrun timer for the current level.
+
+/// A level's fastest run, stored in the `SaveData`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonalBest {
+    /// Physics ticks from the first input, until the level exit.
+    pub ticks:     u64,
+    /// Physics ticks per second of the run, see `SettingsPhysics`.
+    pub tick_rate: u32,
+    /// Each checkpoint's split, in the order they were reached.
+    pub splits:    Vec<Split>,
+}
+
+impl PersonalBest {
+    pub fn seconds(&self) -> f32 {
+        ticks_to_seconds(self.ticks, self.tick_rate)
+    }
+
+    /// Returns the seconds at which the given checkpoint was reached,
+    /// if it was reached in this run.
+    pub fn split_seconds(&self, checkpoint: &str) -> Option<f32> {
+        self.splits
+            .iter()
+            .find(|split| split.checkpoint == checkpoint)
+            .map(|split| ticks_to_seconds(split.ticks, self.tick_rate))
+    }
+}
+
+/// The tick at which a checkpoint was first reached in a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Split {
+    /// The checkpoint's `Checkpoint::id`.
+    pub checkpoint: String,
+    pub ticks:      u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimerState {
+    /// Waiting for the player's first input.
+    Waiting,
+    Running,
+    /// The level exit was reached.
+    Finished,
+}
+
+/// Resource with the time spent in the current level.
+/// Counts physics ticks, so the time is the same for the same inputs, and can be verified
+/// with a replay. The timer starts with the first input, splits when reaching a checkpoint
+/// for the first time, and stops at the level exit. It doesn't run while paused,
+/// because the physics don't.
+/// Reset by the `LevelManager`, when a level is loaded.
+#[derive(Debug, Clone)]
+pub struct LevelTimer {
+    state:      TimerState,
+    ticks:      u64,
+    tick_rate:  u32,
+    splits:     Vec<Split>,
+    best:       Option<PersonalBest>,
+    /// The finished run's delta, shown until the next run starts.
+    last_delta: Option<f32>,
+}
+
+impl LevelTimer {
+    /// Reset the timer for a new run, comparing it against the given personal best.
+    pub fn reset(&mut self, best: Option<PersonalBest>) {
+        let last_delta = match self.state {
+            TimerState::Finished => self.delta_seconds(),
+            _ => None,
+        };
+        *self = Self {
+            best,
+            last_delta,
+            ..Self::default()
+        };
+    }
+
+    pub fn start(&mut self) {
+        if let TimerState::Waiting = self.state {
+            self.state = TimerState::Running;
+        }
+    }
+
+    /// Count a single physics tick, if running.
+    pub fn tick(&mut self, tick_rate: u32) {
+        if let TimerState::Running = self.state {
+            self.ticks += 1;
+            self.tick_rate = tick_rate;
+        }
+    }
+
+    /// Record a split for the checkpoint with the given `Checkpoint::id`,
+    /// if it wasn't reached before in this run.
+    pub fn split(&mut self, checkpoint: &str) {
+        if let TimerState::Running = self.state {
+            if !self
+                .splits
+                .iter()
+                .any(|split| split.checkpoint == checkpoint)
+            {
+                self.splits.push(Split {
+                    checkpoint: checkpoint.to_string(),
+                    ticks:      self.ticks,
+                });
+            }
+        }
+    }
+
+    /// Stop the timer. Returns the finished run, if the timer was running.
+    pub fn finish(&mut self) -> Option<PersonalBest> {
+        if let TimerState::Running = self.state {
+            self.state = TimerState::Finished;
+            Some(PersonalBest {
+                ticks:     self.ticks,
+                tick_rate: self.tick_rate,
+                splits:    self.splits.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn state(&self) -> TimerState {
+        self.state
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn seconds(&self) -> f32 {
+        ticks_to_seconds(self.ticks, self.tick_rate)
+    }
+
+    pub fn best(&self) -> Option<&PersonalBest> {
+        self.best.as_ref()
+    }
+
+    /// Returns the difference in seconds to the personal best,
+    /// at the last split's checkpoint, or at the finish. Negative is faster.
+    /// Before the run starts, the previous level's final delta is returned.
+    pub fn delta_seconds(&self) -> Option<f32> {
+        match self.state {
+            TimerState::Waiting => self.last_delta,
+            TimerState::Finished => {
+                let best = self.best.as_ref()?;
+                Some(self.seconds() - best.seconds())
+            }
+            TimerState::Running => {
+                let best = self.best.as_ref()?;
+                let split = self.splits.last()?;
+                let split_seconds =
+                    ticks_to_seconds(split.ticks, self.tick_rate);
+                best.split_seconds(&split.checkpoint)
+                    .map(|best_split| split_seconds - best_split)
+            }
+        }
+    }
+}
+
+impl Default for LevelTimer {
+    fn default() -> Self {
+        Self {
+            state:      TimerState::Waiting,
+            ticks:      0,
+            tick_rate:  1,
+            splits:     Vec::new(),
+            best:       None,
+            last_delta: None,
+        }
+    }
+}
+
+fn ticks_to_seconds(ticks: u64, tick_rate: u32) -> f32 {
+    ticks as f32 / tick_rate.max(1) as f32
+}
+
+/// Formats seconds as `minutes:seconds.hundredths`, like `1:05.30`.
+pub fn format_time(seconds: f32) -> String {
+    let hundredths = (seconds * 100.0).round() as u64;
+    format!(
+        "{}:{:02}.{:02}",
+        hundredths / 6000,
+        hundredths / 100 % 60,
+        hundredths % 100
+    )
+}
+
+/// Formats a difference in seconds with its sign, like `+1.25` or `-0.40`.
+pub fn format_delta(seconds: f32) -> String {
+    let sign = if seconds < 0.0 { "-" } else { "+" };
+    format!("{}{:.2}", sign, seconds.abs())
+}
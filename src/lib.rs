@@ -0,0 +1,31 @@
+extern crate deathframe;
+
+extern crate amethyst;
+extern crate json;
+extern crate regex;
+extern crate ron;
+#[macro_use]
+extern crate serde;
+extern crate serde_json;
+
+pub mod game;
+pub mod ghost;
+pub mod key_bindings;
+pub mod level_exit;
+pub mod level_timer;
+pub mod map_bounds;
+pub mod physics;
+pub mod player_input;
+pub mod replay;
+pub mod resource_helpers;
+pub mod save_data;
+pub mod score;
+pub mod settings;
+pub mod simulation;
+pub mod ui_buttons;
+pub mod world_helpers;
+
+pub mod components;
+pub mod systems;
+
+pub use deathframe::geo;
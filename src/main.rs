@@ -1,26 +1,11 @@
 extern crate deathframe;
+extern crate hello_amethyst_platformer;
 
 extern crate amethyst;
 #[macro_use]
 extern crate amethyst_editor_sync;
-extern crate json;
-extern crate regex;
-extern crate ron;
-#[macro_use]
-extern crate serde;
-extern crate serde_json;
 extern crate tap;
 
-mod game;
-mod resource_helpers;
-mod settings;
-mod world_helpers;
-
-mod components;
-mod systems;
-
-pub use deathframe::geo;
-
 use amethyst::audio::AudioBundle;
 use amethyst::core::transform::TransformBundle;
 use amethyst::input::InputBundle;
@@ -44,22 +29,66 @@ use tap::*;
 
 use deathframe::custom_game_data::prelude::*;
 
-use components::prelude as comps;
-use resource_helpers::*;
-use systems::prelude::*;
+use hello_amethyst_platformer::components::prelude as comps;
+use hello_amethyst_platformer::replay::{Replay, ReplayMode};
+use hello_amethyst_platformer::resource_helpers::*;
+use hello_amethyst_platformer::systems::prelude::*;
+use hello_amethyst_platformer::{game, settings};
 
 fn main() -> amethyst::Result<()> {
     start_logger();
 
+    let replay_mode = replay_mode_from_args();
     let game_data = build_game_data()?;
 
     let mut game: amethyst::CoreApplication<CustomGameData<DisplayConfig>> =
-        Application::new("./", game::Startup::new(), game_data)?;
+        Application::build("./", game::Startup::new())?
+            .with_resource(replay_mode)
+            .build(game_data)?;
     game.run();
 
     Ok(())
 }
 
+/// Parse the launch options for recording or replaying the player's input:
+/// - `--record <file>` records the run, and saves it to the given RON file when the game stops.
+/// - `--replay <file>` plays the recorded run from the given RON file back.
+fn replay_mode_from_args() -> ReplayMode {
+    let mut replay_mode = ReplayMode::Off;
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--record", Some(path)) => {
+                replay_mode = ReplayMode::Record {
+                    path:   path,
+                    replay: Replay::default(),
+                }
+            }
+            ("--replay", Some(path)) => {
+                let replay = Replay::load(&path).unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                });
+                replay_mode = ReplayMode::Playback {
+                    replay: replay,
+                    frame:  0,
+                }
+            }
+            (arg, _) => {
+                eprintln!(
+                    "Unknown or incomplete option '{}'\n\
+                     Usage: [--record <file> | --replay <file>]",
+                    arg
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
+    replay_mode
+}
+
 fn start_logger() {
     amethyst::start_logger(LoggerConfig {
         level_filter: LogLevelFilter::Error,
@@ -125,8 +154,16 @@ fn build_game_data<'a, 'b>(
                 Size,
                 Solid,
                 Velocity,
+                Checkpoint,
+                Collectible,
+                Ghost,
+                Hazard,
                 JumpRecharge,
+                LevelExit,
+                MovingPlatform,
+                OneWay,
                 Player,
+                PlayerAnimation,
             )
         })
         .tap(|bundle| sync_resources!(bundle, settings::Settings));
@@ -136,6 +173,8 @@ fn build_game_data<'a, 'b>(
         .dispatcher("startup")?
         .dispatcher("ingame")?
         .dispatcher("paused")?
+        .dispatcher("main_menu")?
+        .dispatcher("options")?
         .custom(display_config)
         .with_core_bundle(transform_bundle)?
         .with_core_bundle(render_bundle)?
@@ -149,40 +188,31 @@ fn build_game_data<'a, 'b>(
         ])?
         .with_core(ScaleSpritesSystem, "scale_sprites_system", &[])?
         .with_core(DebugSystem::default(), "debug_system", &[])?
-        .with("ingame", ControlPlayerSystem, "control_player_system", &[])?
-        .with("ingame", GravitySystem, "gravity_system", &[])?
+        .with_core(
+            ui_event_handlers::UIButtonsSystem::default(),
+            "ui_buttons_system",
+            &["input_manager_system"],
+        )?
+        // NOTE: The physics and camera systems run in the fixed timestep `PhysicsDispatcher`, see `Ingame`.
         .with(
             "ingame",
-            LimitVelocitiesSystem,
-            "limit_velocities_system",
-            &["control_player_system", "gravity_system"],
+            UpdatePlayerInputSystem,
+            "update_player_input_system",
+            &[],
         )?
-        .with("ingame", MoveEntitiesSystem, "move_entities_system", &[
-            "control_player_system",
-            "gravity_system",
-            "limit_velocities_system",
-        ])?
-        .with("ingame", CameraSystem, "camera_system", &[
-            "move_entities_system",
-        ])?
-        .with("ingame", ParallaxSystem, "parallax_system", &[
-            "move_entities_system",
-            "camera_system",
-        ])?
-        .with("ingame", CollisionSystem, "collision_system", &[
-            "move_entities_system",
-        ])?
+        .with("ingame", AnimationSystem, "animation_system", &[])?
         .with(
             "ingame",
-            DecreaseVelocitiesSystem,
-            "decrease_velocities_system",
-            &[
-                "control_player_system",
-                "gravity_system",
-                "limit_velocities_system",
-                "move_entities_system",
-            ],
+            PlayerAnimationSystem,
+            "player_animation_system",
+            &[],
         )?
-        .with("ingame", AnimationSystem, "animation_system", &[])?;
+        .with("ingame", HudSystem, "hud_system", &[])?
+        .with(
+            "ingame",
+            ReloadSettingsSystem::default(),
+            "reload_settings_system",
+            &[],
+        )?;
     Ok(game_data)
 }
@@ -0,0 +1,19 @@
+/// The area covered by the current map's tiles.
+/// Inserted by the `MapLoader` when it builds a map;
+/// removed if the map has no tiles.
+/// Used for the kill plane below the map, and as the default `CameraBounds`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MapBounds {
+    pub left:   f32,
+    pub right:  f32,
+    pub bottom: f32,
+    pub top:    f32,
+}
+
+/// The area the camera's view is kept inside of; see `CameraBoundsSystem`.
+/// Set by the `MapLoader` to the `MapBounds`, or by a `CameraBounds` object in the map.
+/// Without bounds, the camera isn't limited.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CameraBounds {
+    pub bounds: Option<MapBounds>,
+}
@@ -0,0 +1,144 @@
+//! Deterministic, fixed timestep gameplay physics.
+//! The physics systems always run with the same delta time, no matter the frame rate,
+//! so jump arcs and collisions are the same on every machine, and recorded inputs replay exactly.
+
+use amethyst::core::timing::Time;
+use amethyst::ecs::{Dispatcher, DispatcherBuilder, World};
+
+use crate::systems::prelude::*;
+
+/// Never run more than this many ticks in a single frame.
+/// After a very long frame, the remaining time is dropped, instead of trying to catch up.
+const MAX_TICKS_PER_FRAME: u32 = 8;
+
+/// Runs the physics systems in fixed ticks. Frame time is accumulated,
+/// and as many ticks are run, as fit into the accumulated time.
+pub struct PhysicsDispatcher<'a, 'b> {
+    dispatcher:   Dispatcher<'a, 'b>,
+    tick_seconds: f32,
+    accumulator:  f32,
+}
+
+impl<'a, 'b> PhysicsDispatcher<'a, 'b> {
+    /// Create a new dispatcher, running `tick_rate` ticks per second.
+    /// Sets up the systems' resources and components in the given world.
+    pub fn new(world: &mut World, tick_rate: u32) -> Self {
+        let mut dispatcher = build_dispatcher();
+        dispatcher.setup(&mut world.res);
+        Self {
+            dispatcher:   dispatcher,
+            tick_seconds: tick_seconds(tick_rate),
+            accumulator:  0.0,
+        }
+    }
+
+    /// Returns the duration of a single tick, in seconds.
+    pub fn tick_seconds(&self) -> f32 {
+        self.tick_seconds
+    }
+
+    /// Change the amount of ticks per second.
+    pub fn set_tick_rate(&mut self, tick_rate: u32) {
+        self.tick_seconds = tick_seconds(tick_rate);
+    }
+
+    /// Add the given frame time to the accumulator, and run as many ticks as fit into it.
+    /// Returns the amount of ticks run.
+    pub fn update(&mut self, world: &mut World, delta_seconds: f32) -> u32 {
+        self.accumulator += delta_seconds;
+        let mut ticks = 0;
+        while self.accumulator >= self.tick_seconds {
+            if ticks >= MAX_TICKS_PER_FRAME {
+                self.accumulator = 0.0;
+                break;
+            }
+            self.tick(world);
+            self.accumulator -= self.tick_seconds;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    /// Run the physics systems once.
+    /// `Time::delta_seconds` is the tick duration while the systems run,
+    /// and is restored to the frame's delta afterwards.
+    pub fn tick(&mut self, world: &mut World) {
+        let frame_delta_seconds = world.read_resource::<Time>().delta_seconds();
+        world
+            .write_resource::<Time>()
+            .set_delta_seconds(self.tick_seconds);
+        self.dispatcher.dispatch(&world.res);
+        world.maintain();
+        world
+            .write_resource::<Time>()
+            .set_delta_seconds(frame_delta_seconds);
+    }
+}
+
+fn tick_seconds(tick_rate: u32) -> f32 {
+    1.0 / tick_rate.max(1) as f32
+}
+
+/// The physics systems:
+/// death and respawn, level timer, collectibles, control, gravity, limit velocities, moving platforms, one-way platforms, move,
+/// collision, level exits, decrease velocities and ghosts.
+/// The `ReplaySystem` runs first, so recorded inputs are recorded and replayed per tick.
+/// The camera, camera bounds and parallax systems run last, so every tick ends
+/// with the camera clamped to its bounds, and the backgrounds following it.
+fn build_dispatcher<'a, 'b>() -> Dispatcher<'a, 'b> {
+    DispatcherBuilder::new()
+        .with(ReplaySystem, "replay_system", &[])
+        .with(DeathSystem, "death_system", &["replay_system"])
+        .with(LevelTimerSystem, "level_timer_system", &["death_system"])
+        .with(CollectSystem, "collect_system", &["death_system"])
+        .with(ControlPlayerSystem, "control_player_system", &[
+            "death_system",
+        ])
+        .with(GravitySystem, "gravity_system", &["death_system"])
+        .with(LimitVelocitiesSystem, "limit_velocities_system", &[
+            "control_player_system",
+            "gravity_system",
+        ])
+        .with(MovePlatformsSystem, "move_platforms_system", &[
+            "control_player_system",
+            "limit_velocities_system",
+        ])
+        .with(OneWaySystem, "one_way_system", &["move_platforms_system"])
+        .with(MoveEntitiesSystem, "move_entities_system", &[
+            "control_player_system",
+            "gravity_system",
+            "limit_velocities_system",
+            "move_platforms_system",
+            "one_way_system",
+        ])
+        .with(
+            FinishMovingPlatformsSystem,
+            "finish_moving_platforms_system",
+            &["move_entities_system"],
+        )
+        .with(CollisionSystem, "collision_system", &[
+            "move_entities_system",
+            "finish_moving_platforms_system",
+        ])
+        .with(LevelExitSystem, "level_exit_system", &["collision_system"])
+        .with(DecreaseVelocitiesSystem, "decrease_velocities_system", &[
+            "control_player_system",
+            "gravity_system",
+            "limit_velocities_system",
+            "move_entities_system",
+            "finish_moving_platforms_system",
+        ])
+        .with(GhostSystem, "ghost_system", &[
+            "level_timer_system",
+            "move_entities_system",
+        ])
+        .with(CameraSystem, "camera_system", &["collision_system"])
+        .with(CameraBoundsSystem, "camera_bounds_system", &[
+            "camera_system",
+        ])
+        .with(ParallaxSystem, "parallax_system", &[
+            "camera_system",
+            "camera_bounds_system",
+        ])
+        .build()
+}
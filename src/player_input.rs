@@ -0,0 +1,58 @@
+use amethyst::input::InputHandler;
+
+use crate::settings::SettingsInput;
+
+/// The player's input for a single frame.
+/// Written by the `UpdatePlayerInputSystem` from the live input,
+/// or directly by a script in headless simulations.
+/// `ControlPlayerSystem` only reads input from here.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInput {
+    /// Value of the `player_x` axis, between `-1.0` (left) and `1.0` (right).
+    /// Values in between come from a controller stick.
+    pub x:    f32,
+    /// Value of the `player_y` axis, between `-1.0` (down) and `1.0` (up).
+    #[serde(default)]
+    pub y:    f32,
+    /// If the `player_jump` action is down.
+    pub jump: bool,
+    /// If the `player_run` action is down.
+    pub run:  bool,
+}
+
+impl PlayerInput {
+    /// Read the player's bindings from the given `InputHandler`.
+    /// Used for the live input by the `UpdatePlayerInputSystem`.
+    pub fn from_input_handler(
+        input_handler: &InputHandler<String, String>,
+    ) -> Self {
+        Self {
+            x:    axis_value(input_handler, "player_x"),
+            y:    axis_value(input_handler, "player_y"),
+            jump: input_handler.action_is_down("player_jump").unwrap_or(false),
+            run:  input_handler.action_is_down("player_run").unwrap_or(false),
+        }
+    }
+
+    /// Returns `true` if any input is given.
+    /// Stick values inside the deadzone (stick drift) don't count.
+    pub fn is_active(&self, settings: &SettingsInput) -> bool {
+        settings.analog_scale(self.x) != 0.0
+            || settings.analog_scale(self.y) != 0.0
+            || self.jump
+            || self.run
+    }
+}
+
+/// Returns the value of the given axis.
+/// If it isn't pressed, returns the value of its controller stick counterpart,
+/// the axis with the same name and a `_stick` suffix (see `bindings.ron`).
+/// The stick's deadzone is applied by the `ControlPlayerSystem`.
+fn axis_value(input_handler: &InputHandler<String, String>, axis: &str) -> f32 {
+    match input_handler.axis_value(axis) {
+        Some(value) if value != 0.0 => value as f32,
+        _ => input_handler
+            .axis_value(&format!("{}_stick", axis))
+            .unwrap_or(0.0) as f32,
+    }
+}
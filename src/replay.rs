@@ -0,0 +1,102 @@
+use std::fs;
+
+use ron::ser::PrettyConfig;
+
+use crate::player_input::PlayerInput;
+use crate::resource_helpers::*;
+
+/// A single recorded frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub delta_seconds: f32,
+    pub input:         PlayerInput,
+}
+
+/// The player's input for every frame of a run, and where and how it was recorded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Replay {
+    /// The level the run started on; the following levels are reached through level exits.
+    pub level:     Option<String>,
+    /// The physics ticks per second, the run was recorded with.
+    pub tick_rate: u32,
+    pub frames:    Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// Load a replay from the RON file at the given path.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let replay_raw = read_file(path)
+            .map_err(|e| format!("Couldn't read replay '{}': {}", path, e))?;
+        ron::de::from_str(&replay_raw)
+            .map_err(|e| format!("Couldn't parse replay '{}': {}", path, e))
+    }
+
+    /// Save the replay as a RON file to the given path.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let replay_raw =
+            ron::ser::to_string_pretty(self, PrettyConfig::default())
+                .map_err(|e| format!("Couldn't serialize replay: {}", e))?;
+        fs::write(path, replay_raw)
+            .map_err(|e| format!("Couldn't write replay '{}': {}", path, e))
+    }
+}
+
+/// Resource, which decides if the player's input is recorded, played back, or neither.
+pub enum ReplayMode {
+    /// Use live input, don't record anything.
+    Off,
+    /// Use live input, and record every frame. Saved to `path` when the game stops.
+    Record { path: String, replay: Replay },
+    /// Feed the recorded frames back, instead of live input.
+    Playback { replay: Replay, frame: usize },
+}
+
+impl ReplayMode {
+    /// Start the recording over on the given level, if recording.
+    /// Called when a run is started from the main menu, or the level is restarted.
+    /// Level exits keep recording, so a run across several levels is replayed as a whole.
+    pub fn restart(&mut self, level: &str, tick_rate: u32) {
+        if let ReplayMode::Record { replay, .. } = self {
+            replay.level = Some(level.to_string());
+            replay.tick_rate = tick_rate;
+            replay.frames.clear();
+        }
+    }
+
+    /// Returns the level to start the playback on,
+    /// if playing back, and no frame has been played yet.
+    pub fn playback_level(&self) -> Option<&str> {
+        match self {
+            ReplayMode::Playback { replay, frame: 0 } => {
+                replay.level.as_ref().map(String::as_str)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the tick rate to play the replay back with, if playing back.
+    /// Replays without a recorded tick rate use the settings' tick rate.
+    pub fn playback_tick_rate(&self) -> Option<u32> {
+        match self {
+            ReplayMode::Playback { replay, .. } if replay.tick_rate > 0 => {
+                Some(replay.tick_rate)
+            }
+            _ => None,
+        }
+    }
+
+    /// Save the recorded replay, if recording.
+    pub fn save(&self) -> Result<(), String> {
+        match self {
+            ReplayMode::Record { path, replay } => replay.save(path),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for ReplayMode {
+    fn default() -> Self {
+        ReplayMode::Off
+    }
+}
@@ -1,5 +1,6 @@
+use std::env;
 use std::fmt::Display;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
 use std::io::prelude::*;
 use std::path::Path;
@@ -7,6 +8,7 @@ use std::path::Path;
 use amethyst::utils::application_root_dir;
 
 const RESOURCES_DIRNAME: &str = "resources";
+const USER_DATA_DIRNAME: &str = "hello-amethyst-platformer";
 
 pub fn resources_dir() -> String {
     format!("{}/{}", application_root_dir(), RESOURCES_DIRNAME)
@@ -25,3 +27,52 @@ where
     file.read_to_string(&mut content)?;
     Ok(content)
 }
+
+/// Returns the directory for the player's save data:
+/// `$XDG_DATA_HOME` or `~/.local/share` on Linux, `~/Library/Application Support` on macOS,
+/// and `%APPDATA%` on Windows. Falls back to the application's root directory.
+pub fn user_data_dir() -> String {
+    let base = if cfg!(target_os = "windows") {
+        env::var("APPDATA").ok()
+    } else if cfg!(target_os = "macos") {
+        env::var("HOME")
+            .ok()
+            .map(|home| format!("{}/Library/Application Support", home))
+    } else {
+        env::var("XDG_DATA_HOME").ok().or_else(|| {
+            env::var("HOME")
+                .ok()
+                .map(|home| format!("{}/.local/share", home))
+        })
+    };
+    match base {
+        Some(base) => format!("{}/{}", base, USER_DATA_DIRNAME),
+        None => application_root_dir(),
+    }
+}
+
+pub fn user_data_file<T: ToString>(path: T) -> String {
+    format!("{}/{}", user_data_dir(), path.to_string())
+}
+
+/// Writes the content to a temporary file next to the given path,
+/// and renames it to the path once it is written completely.
+/// The file is never left half-written, even if the game crashes while saving.
+/// Missing parent directories are created.
+pub fn write_file_atomic<P, C>(path: P, content: C) -> Result<(), io::Error>
+where
+    P: AsRef<Path>,
+    C: AsRef<[u8]>,
+{
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(content.as_ref())?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)
+}
@@ -0,0 +1,305 @@
+//! The player's progress, saved between sessions.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+use amethyst::ecs::World;
+use ron::ser::PrettyConfig;
+
+use crate::level_timer::PersonalBest;
+use crate::resource_helpers::*;
+use crate::score::{LevelTally, Score};
+
+/// Version of the save file format.
+/// Increase this when changing `SaveData`, and handle the old version in `SaveData::parse`.
+pub const SAVE_DATA_VERSION: u32 = 3;
+
+const SAVE_FILENAME: &str = "save.ron";
+
+/// Resource with everything saved to the save file.
+/// Loaded at `Startup`; missing fields get their default values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub version:         u32,
+    /// Names of the levels, which can be selected in the main menu.
+    pub unlocked_levels: Vec<String>,
+    /// Collected items per level, see `Score`.
+    pub tallies:         HashMap<String, LevelTally>,
+    /// Fastest run, per level.
+    pub best_times:      HashMap<String, PersonalBest>,
+    pub settings:        SettingsOverrides,
+    /// Set if the save file is from a newer version of the game.
+    /// The file is then never saved over, so its newer data isn't lost.
+    #[serde(skip)]
+    pub is_read_only:    bool,
+}
+
+/// Settings changed in the options, which override the defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsOverrides {
+    /// Music and sound volume, between `0.0` and `1.0`.
+    pub volume:      Option<f32>,
+    /// Show a ghost of the best run, while playing a level. Shown by default.
+    pub show_ghosts: Option<bool>,
+}
+
+impl SaveData {
+    /// Returns `true` if ghosts of best runs should be shown.
+    pub fn show_ghosts(&self) -> bool {
+        self.settings.show_ghosts.unwrap_or(true)
+    }
+
+    /// Returns the path to the save file, in the user data directory.
+    pub fn path() -> String {
+        user_data_file(SAVE_FILENAME)
+    }
+
+    /// Load the save file.
+    /// If there is no save file yet, or it is corrupt, the default `SaveData` is returned;
+    /// a corrupt file is kept with the extension `.corrupt`, so it isn't lost with the next save.
+    /// If the file is from a newer version of the game, the default `SaveData` is returned,
+    /// and the file is kept as it is (see `is_read_only`).
+    pub fn load() -> Self {
+        let path = Self::path();
+        let save_raw = match read_file(&path) {
+            Ok(save_raw) => save_raw,
+            Err(_) => return Self::default(),
+        };
+        match Self::parse(&save_raw) {
+            Ok(save_data) => save_data,
+            Err(e @ SaveParseError::NewerVersion(_)) => {
+                eprintln!(
+                    "Couldn't load save file '{}', progress won't be saved: {}",
+                    path, e
+                );
+                Self {
+                    is_read_only: true,
+                    ..Self::default()
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "Couldn't parse save file '{}', starting a new save: {}",
+                    path, e
+                );
+                if let Err(e) = fs::rename(&path, format!("{}.corrupt", path)) {
+                    eprintln!("Couldn't back up corrupt save file: {}", e);
+                }
+                Self::default()
+            }
+        }
+    }
+
+    /// Write the save file atomically, see `write_file_atomic`.
+    /// A save file from a newer version of the game isn't overwritten.
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if self.is_read_only {
+            return Err(format!(
+                "Not saving over save file '{}' from a newer version",
+                path
+            ));
+        }
+        let save_raw =
+            ron::ser::to_string_pretty(self, PrettyConfig::default())
+                .map_err(|e| format!("Couldn't serialize save data: {}", e))?;
+        write_file_atomic(&path, save_raw)
+            .map_err(|e| format!("Couldn't write save file '{}': {}", path, e))
+    }
+
+    /// Parse the save file's content, and update it from older versions
+    /// to the current version. Newer versions can't be read.
+    pub fn parse(save_raw: &str) -> Result<Self, SaveParseError> {
+        let value = ron::Value::from_str(save_raw)?;
+        let version = value.clone().into_rust::<SaveVersion>()?.version;
+        if version > SAVE_DATA_VERSION {
+            return Err(SaveParseError::NewerVersion(version));
+        }
+        let mut save_data = match version {
+            1 => value.into_rust::<SaveDataV1>()?.into(),
+            2 => value.into_rust::<SaveDataV2>()?.into(),
+            _ => value.into_rust::<Self>()?,
+        };
+        save_data.version = SAVE_DATA_VERSION;
+        Ok(save_data)
+    }
+
+    pub fn is_level_unlocked(&self, level: &str) -> bool {
+        self.unlocked_levels
+            .iter()
+            .any(|unlocked| unlocked == level)
+    }
+
+    pub fn unlock_level<T: ToString>(&mut self, level: T) {
+        let level = level.to_string();
+        if !self.is_level_unlocked(&level) {
+            self.unlocked_levels.push(level);
+        }
+    }
+
+    /// Record the level's finished run.
+    /// Returns `true` if it is a new personal best.
+    pub fn record_run<T: ToString>(
+        &mut self,
+        level: T,
+        run: PersonalBest,
+    ) -> bool {
+        let level = level.to_string();
+        let is_best = self
+            .best_times
+            .get(&level)
+            .map(|best| run.seconds() < best.seconds())
+            .unwrap_or(true);
+        if is_best {
+            self.best_times.insert(level, run);
+        }
+        is_best
+    }
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version:         SAVE_DATA_VERSION,
+            unlocked_levels: Vec::new(),
+            tallies:         HashMap::new(),
+            best_times:      HashMap::new(),
+            settings:        SettingsOverrides::default(),
+            is_read_only:    false,
+        }
+    }
+}
+
+/// Errors that can occur while parsing a save file.
+#[derive(Debug)]
+pub enum SaveParseError {
+    /// The save file isn't valid RON, or doesn't match the `SaveData`.
+    Ron(ron::de::Error),
+    /// The save file is from a newer version of the game, with the given save version.
+    NewerVersion(u32),
+}
+
+impl From<ron::de::Error> for SaveParseError {
+    fn from(error: ron::de::Error) -> Self {
+        SaveParseError::Ron(error)
+    }
+}
+
+impl fmt::Display for SaveParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveParseError::Ron(error) => write!(f, "{}", error),
+            SaveParseError::NewerVersion(version) => write!(
+                f,
+                "save file version {} is newer than the game's version {}",
+                version, SAVE_DATA_VERSION
+            ),
+        }
+    }
+}
+
+/// Only reads the version of a save file.
+#[derive(Deserialize)]
+struct SaveVersion {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Save data version 1, with best times in seconds.
+#[derive(Deserialize)]
+#[serde(default)]
+struct SaveDataV1 {
+    unlocked_levels: Vec<String>,
+    tallies:         HashMap<String, LevelTally>,
+    settings:        SettingsOverrides,
+}
+
+impl Default for SaveDataV1 {
+    fn default() -> Self {
+        let save_data = SaveData::default();
+        Self {
+            unlocked_levels: save_data.unlocked_levels,
+            tallies:         save_data.tallies,
+            settings:        save_data.settings,
+        }
+    }
+}
+
+/// Best times of version 1 weren't counted in physics ticks,
+/// so they can't be compared with new runs, and are dropped.
+impl From<SaveDataV1> for SaveData {
+    fn from(save_data: SaveDataV1) -> Self {
+        Self {
+            unlocked_levels: save_data.unlocked_levels,
+            tallies: save_data.tallies,
+            settings: save_data.settings,
+            ..Self::default()
+        }
+    }
+}
+
+/// Save data version 2, with splits in the order the checkpoints were reached.
+#[derive(Deserialize)]
+#[serde(default)]
+struct SaveDataV2 {
+    unlocked_levels: Vec<String>,
+    tallies:         HashMap<String, LevelTally>,
+    best_times:      HashMap<String, PersonalBestV2>,
+    settings:        SettingsOverrides,
+}
+
+impl Default for SaveDataV2 {
+    fn default() -> Self {
+        let save_data = SaveData::default();
+        Self {
+            unlocked_levels: save_data.unlocked_levels,
+            tallies:         save_data.tallies,
+            best_times:      HashMap::new(),
+            settings:        save_data.settings,
+        }
+    }
+}
+
+/// A personal best of version 2; its splits are ignored.
+#[derive(Deserialize)]
+struct PersonalBestV2 {
+    ticks:     u64,
+    tick_rate: u32,
+}
+
+/// Splits of version 2 don't say which checkpoint they belong to,
+/// so they can't be compared with new runs, and are dropped. The best times are kept.
+impl From<SaveDataV2> for SaveData {
+    fn from(save_data: SaveDataV2) -> Self {
+        Self {
+            unlocked_levels: save_data.unlocked_levels,
+            tallies: save_data.tallies,
+            best_times: save_data
+                .best_times
+                .into_iter()
+                .map(|(level, best)| {
+                    (level, PersonalBest {
+                        ticks:     best.ticks,
+                        tick_rate: best.tick_rate,
+                        splits:    Vec::new(),
+                    })
+                })
+                .collect(),
+            settings: save_data.settings,
+            ..Self::default()
+        }
+    }
+}
+
+/// Copy the collected items from the `Score` into the `SaveData` resource,
+/// and write the save file. Errors are only printed; the game goes on without saving.
+pub fn save_game(world: &World) {
+    let mut save_data = world.write_resource::<SaveData>();
+    save_data.tallies = world.read_resource::<Score>().levels.clone();
+    if let Err(e) = save_data.save() {
+        eprintln!("{}", e);
+    }
+}
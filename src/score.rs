@@ -0,0 +1,52 @@
+use std::collections::{HashMap, HashSet};
+
+/// The collectibles collected in a level, over all of its runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LevelTally {
+    /// Ids of the collected `Collectible`s.
+    pub collected: HashSet<String>,
+    /// Sum of the collected `Collectible`s' values.
+    pub score:     u32,
+}
+
+/// Resource with the collected items of every level.
+/// Kept across level restarts, so collectibles are only counted once per level.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Score {
+    pub levels: HashMap<String, LevelTally>,
+    /// The currently running level, set by the `LevelManager`.
+    #[serde(skip)]
+    level:      String,
+}
+
+impl Score {
+    /// Set the level, to which collected items are counted.
+    pub fn set_level<T: ToString>(&mut self, level: T) {
+        self.level = level.to_string();
+    }
+
+    /// Returns the current level's tally, if anything was collected in it.
+    pub fn tally(&self) -> Option<&LevelTally> {
+        self.levels.get(&self.level)
+    }
+
+    /// Returns `true` if the collectible with the given id
+    /// was already collected in the current level.
+    pub fn is_collected(&self, id: &str) -> bool {
+        self.tally()
+            .map(|tally| tally.collected.contains(id))
+            .unwrap_or(false)
+    }
+
+    /// Count the collectible with the given id and value for the current level.
+    /// Returns `false` if it was already collected.
+    pub fn collect<T: ToString>(&mut self, id: T, value: u32) -> bool {
+        let tally = self.levels.entry(self.level.clone()).or_default();
+        if tally.collected.insert(id.to_string()) {
+            tally.score += value;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -4,18 +4,48 @@ use deathframe::geo::Vector;
 use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
 
+use crate::resource_helpers::*;
+
 pub mod prelude {
+    pub use super::load_settings;
+    pub use super::settings_path;
     pub use super::Settings;
     pub use super::SettingsCamera;
+    pub use super::SettingsInput;
+    pub use super::SettingsPhysics;
     pub use super::SettingsPlayer;
+    pub use super::SettingsPlayerDeath;
     pub use super::SettingsPlayerQuickTurnaround;
+    pub use super::SettingsPlayerWallJump;
 }
 
 // TODO: Refactor this. Less fields; more structs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
-    pub camera: SettingsCamera,
-    pub player: SettingsPlayer,
+    pub physics: SettingsPhysics,
+    pub input:   SettingsInput,
+    pub camera:  SettingsCamera,
+    pub player:  SettingsPlayer,
+}
+
+/// Returns the path to the `settings.ron` file.
+pub fn settings_path() -> String {
+    resource("config/settings.ron")
+}
+
+/// Reads and parses the `settings.ron` file.
+/// Returns a message describing the problem, if the file can't be read or parsed.
+pub fn load_settings() -> Result<Settings, String> {
+    let settings_raw = read_file(settings_path())
+        .map_err(|e| format!("Couldn't read settings.ron file: {}", e))?;
+    ron::Value::from_str(&settings_raw)
+        .and_then(|value| value.into_rust())
+        .map_err(|e| format!("Couldn't parse settings.ron file: {}", e))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsPhysics {
+    pub tick_rate: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +56,28 @@ pub struct SettingsCamera {
     pub deadzone:   Vector,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsInput {
+    /// Stick values closer to the center than this are ignored, between `0.0` and `1.0`.
+    pub stick_deadzone:               f32,
+    /// Exponent of the curve mapping a stick's tilt to the player's acceleration.
+    pub analog_acceleration_exponent: f32,
+}
+
+impl SettingsInput {
+    /// Returns the axis value with the deadzone removed, rescaled to `-1.0` to `1.0`,
+    /// and shaped by the acceleration curve. Digital input stays `-1.0`, `0.0` or `1.0`.
+    pub fn analog_scale(&self, value: f32) -> f32 {
+        let deadzone = self.stick_deadzone.max(0.0).min(0.99);
+        let magnitude = value.abs().min(1.0);
+        if magnitude <= deadzone {
+            return 0.0;
+        }
+        let scaled = (magnitude - deadzone) / (1.0 - deadzone);
+        scaled.powf(self.analog_acceleration_exponent) * value.signum()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettingsPlayer {
     pub size:                   Vector,
@@ -41,6 +93,23 @@ pub struct SettingsPlayer {
     pub quick_turnaround:       SettingsPlayerQuickTurnaround,
     pub air_quick_turnaround:   SettingsPlayerQuickTurnaround,
     pub is_double_jump_enabled: bool,
+    pub coyote_time_ms:         u64,
+    pub jump_buffer_ms:         u64,
+    pub wall_jump:              SettingsPlayerWallJump,
+    pub death:                  SettingsPlayerDeath,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsPlayerWallJump {
+    pub is_enabled:       bool,
+    pub strength:         Vector,
+    pub input_lockout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsPlayerDeath {
+    pub respawn_delay_ms:    u64,
+    pub kill_plane_distance: f32,
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -0,0 +1,172 @@
+//! Headless simulation of the gameplay systems.
+//! Builds a `World` with the fixed timestep `PhysicsDispatcher`, which includes the camera systems;
+//! no window, renderer or audio device is needed, so it can run in tests on any machine.
+
+use amethyst::config::Config;
+use amethyst::core::shrev::EventChannel;
+use amethyst::core::timing::Time;
+use amethyst::core::transform::Transform;
+use amethyst::ecs::{Join, World};
+use amethyst::input::{Bindings, ControllerEvent, InputEvent, InputHandler};
+use amethyst::renderer::{
+    Camera as AmethystCamera,
+    Rgba,
+    SpriteRender,
+    Transparent,
+};
+
+use crate::components::prelude::*;
+use crate::game::{MapLoadError, MapLoader};
+use crate::physics::PhysicsDispatcher;
+use crate::player_input::PlayerInput;
+use crate::replay::Replay;
+use crate::resource_helpers::*;
+use crate::settings::Settings;
+
+pub struct Simulation<'a, 'b> {
+    pub world:  World,
+    physics:    PhysicsDispatcher<'a, 'b>,
+    map_loader: MapLoader,
+}
+
+impl<'a, 'b> Simulation<'a, 'b> {
+    /// Create a new simulation with the given settings.
+    /// Every step runs a single physics tick, at the settings' tick rate.
+    pub fn new(settings: Settings) -> Self {
+        let mut world = World::new();
+        let physics =
+            PhysicsDispatcher::new(&mut world, settings.physics.tick_rate);
+        register_components(&mut world);
+
+        let mut time = Time::default();
+        time.set_delta_seconds(physics.tick_seconds());
+        world.add_resource(time);
+        world.add_resource(settings);
+        world.add_resource(PlayerInput::default());
+        world.add_resource(EventChannel::<InputEvent<String>>::new());
+        let mut input_handler = InputHandler::<String, String>::new();
+        input_handler.bindings =
+            Bindings::load(resource("config/bindings.ron"));
+        world.add_resource(input_handler);
+
+        Self {
+            world:      world,
+            physics:    physics,
+            map_loader: MapLoader::new().load_graphics(false),
+        }
+    }
+
+    /// Load and build the map with the given filename, without any graphics.
+    /// A previously loaded map is unloaded first.
+    pub fn load_map<T>(&mut self, filename: T) -> Result<(), MapLoadError>
+    where
+        T: ToString,
+    {
+        let mut map_loader = MapLoader::new().load_graphics(false);
+        map_loader.load_map(filename)?;
+        self.map_loader.unload(&mut self.world);
+        map_loader.build(&mut self.world);
+        self.map_loader = map_loader;
+        Ok(())
+    }
+
+    /// Set the player's input, used for all following frames, until it is set again.
+    /// This stands in for the live `InputHandler` and `InputManager`.
+    pub fn set_input(&mut self, input: PlayerInput) {
+        *self.world.write_resource::<PlayerInput>() = input;
+    }
+
+    /// Send a controller event, as if it came from a gamepad, and set the player's input
+    /// from the controller bindings in `bindings.ron`. The input is used for all following
+    /// frames, until another event is sent, or it is set with `set_input`.
+    /// The controller must be connected first (`ControllerEvent::ControllerConnected`).
+    pub fn send_controller_event(&mut self, event: ControllerEvent) {
+        let mut input_handler =
+            self.world.write_resource::<InputHandler<String, String>>();
+        input_handler.send_controller_event(
+            &event,
+            &mut self
+                .world
+                .write_resource::<EventChannel<InputEvent<String>>>(),
+        );
+        *self.world.write_resource::<PlayerInput>() =
+            PlayerInput::from_input_handler(&input_handler);
+    }
+
+    /// Run a single physics tick.
+    pub fn step(&mut self) {
+        self.physics.tick(&mut self.world);
+    }
+
+    /// Run a frame with the given duration: as many physics ticks as fit into it,
+    /// like the running game does.
+    /// Returns the amount of ticks run.
+    pub fn update(&mut self, delta_seconds: f32) -> u32 {
+        self.physics.update(&mut self.world, delta_seconds)
+    }
+
+    /// Returns the duration of a single physics tick, in seconds.
+    pub fn tick_seconds(&self) -> f32 {
+        self.physics.tick_seconds()
+    }
+
+    /// Run the given amount of steps.
+    pub fn run_frames(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// Play the recorded ticks back, with their recorded inputs.
+    pub fn run_replay(&mut self, replay: &Replay) {
+        for frame in &replay.frames {
+            self.set_input(frame.input.clone());
+            self.step();
+        }
+    }
+
+    /// Returns the player's position, if a player exists.
+    pub fn player_position(&self) -> Option<(f32, f32)> {
+        let players = self.world.read_storage::<Player>();
+        let transforms = self.world.read_storage::<Transform>();
+        (&players, &transforms).join().next().map(|(_, transform)| {
+            let translation = transform.translation();
+            (translation.x, translation.y)
+        })
+    }
+
+    /// Returns the camera's position, the bottom-left corner of its view,
+    /// if a camera exists.
+    pub fn camera_position(&self) -> Option<(f32, f32)> {
+        let cameras = self.world.read_storage::<Camera>();
+        let transforms = self.world.read_storage::<Transform>();
+        (&cameras, &transforms).join().next().map(|(_, transform)| {
+            let translation = transform.translation();
+            (translation.x, translation.y)
+        })
+    }
+
+    /// Returns a copy of the player's velocity, if a player exists.
+    pub fn player_velocity(&self) -> Option<(f32, f32)> {
+        let players = self.world.read_storage::<Player>();
+        let velocities = self.world.read_storage::<Velocity>();
+        (&players, &velocities)
+            .join()
+            .next()
+            .map(|(_, velocity)| (velocity.x, velocity.y))
+    }
+}
+
+/// Register components, which the `MapLoader` adds to entities,
+/// but which aren't used by any of the simulated systems.
+fn register_components(world: &mut World) {
+    world.register::<AmethystCamera>();
+    world.register::<SpriteRender>();
+    world.register::<Transparent>();
+    world.register::<Rgba>();
+    world.register::<ScaleOnce>();
+    world.register::<Animation>();
+    world.register::<Parallax>();
+    world.register::<JumpRecharge>();
+    world.register::<Pushable>();
+}
@@ -0,0 +1,93 @@
+use amethyst::core::transform::Transform;
+
+use super::system_prelude::*;
+use crate::map_bounds::CameraBounds;
+
+/// Keeps the camera's view inside the `CameraBounds`, so nothing past the map's edges is shown.
+/// On an axis where the view is larger than the bounds, the bounds are centered in the view.
+/// Runs after the `CameraSystem` and before the `ParallaxSystem`,
+/// so parallax backgrounds follow the clamped position.
+pub struct CameraBoundsSystem;
+
+impl<'a> System<'a> for CameraBoundsSystem {
+    type SystemData = (
+        Read<'a, CameraBounds>,
+        ReadStorage<'a, Camera>,
+        ReadStorage<'a, Size>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Velocity>,
+    );
+
+    fn run(
+        &mut self,
+        (camera_bounds, cameras, sizes, mut transforms, mut velocities): Self::SystemData,
+    ) {
+        let bounds = match camera_bounds.bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        for (_, size, transform, velocity_opt) in
+            (&cameras, &sizes, &mut transforms, (&mut velocities).maybe())
+                .join()
+        {
+            // The camera's position is the bottom-left corner of its view
+            let translation = transform.translation();
+            let x =
+                clamp_axis(translation.x, size.w, bounds.left, bounds.right);
+            let y =
+                clamp_axis(translation.y, size.h, bounds.bottom, bounds.top);
+
+            if let Some(velocity) = velocity_opt {
+                // Don't keep moving against the bounds, also when already resting on them
+                velocity.x = clamp_velocity(
+                    velocity.x,
+                    x,
+                    size.w,
+                    bounds.left,
+                    bounds.right,
+                );
+                velocity.y = clamp_velocity(
+                    velocity.y,
+                    y,
+                    size.h,
+                    bounds.bottom,
+                    bounds.top,
+                );
+            }
+            transform.set_x(x);
+            transform.set_y(y);
+        }
+    }
+}
+
+/// Returns the view's start position on one axis, clamped so the view stays
+/// between `min` and `max`; or centered on them, if the view is larger.
+fn clamp_axis(pos: f32, view_size: f32, min: f32, max: f32) -> f32 {
+    if view_size >= max - min {
+        min + (max - min - view_size) * 0.5
+    } else {
+        pos.max(min).min(max - view_size)
+    }
+}
+
+/// Returns the velocity on one axis, without the part that points out of the bounds,
+/// for a view at the clamped position `pos`.
+/// A view larger than the bounds is centered on them, and doesn't move at all.
+fn clamp_velocity(
+    velocity: f32,
+    pos: f32,
+    view_size: f32,
+    min: f32,
+    max: f32,
+) -> f32 {
+    if view_size >= max - min {
+        0.0
+    } else if pos <= min {
+        velocity.max(0.0)
+    } else if pos + view_size >= max {
+        velocity.min(0.0)
+    } else {
+        velocity
+    }
+}
@@ -0,0 +1,68 @@
+use amethyst::audio::{output::Output, Source};
+use deathframe::handlers::AudioHandles;
+
+use super::system_prelude::*;
+use crate::score::Score;
+
+/// Removes `Collectible`s the player has just entered, counts them to the `Score`,
+/// and plays their sound. Ghosts (already collected in a previous run) are ignored.
+pub struct CollectSystem;
+
+impl<'a> System<'a> for CollectSystem {
+    type SystemData = (
+        Entities<'a>,
+        Option<Read<'a, Output>>,
+        Read<'a, AudioHandles>,
+        Read<'a, AssetStorage<Source>>,
+        Write<'a, Score>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Collision>,
+        ReadStorage<'a, Collectible>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            audio_output,
+            audio_handles,
+            audio_source,
+            mut score,
+            players,
+            collisions,
+            collectibles,
+        ): Self::SystemData,
+    ) {
+        for (player, collision) in (&players, &collisions).join() {
+            if player.is_dead() {
+                continue;
+            }
+            for (entity, collectible) in (&entities, &collectibles).join() {
+                if collectible.is_ghost {
+                    continue;
+                }
+                if let Some(coll_data) = collision.collision_with(entity.id()) {
+                    if coll_data.side.is_inner()
+                        && coll_data.state.is_entering()
+                        && score.collect(&collectible.id, collectible.value)
+                    {
+                        // There is no audio output in headless simulations
+                        if let (Some(sound), Some(output)) =
+                            (&collectible.sound, &audio_output)
+                        {
+                            if audio_handles.get(sound).is_some() {
+                                audio_handles.play_with(
+                                    sound,
+                                    &audio_source,
+                                    output,
+                                    None,
+                                );
+                            }
+                        }
+                        entities.delete(entity).unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
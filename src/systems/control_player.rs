@@ -1,8 +1,10 @@
 use amethyst::audio::{output::Output, Source};
+use amethyst::core::transform::Transform;
 use deathframe::handlers::AudioHandles;
 
 use super::system_prelude::*;
 use crate::geo::Side;
+use crate::player_input::PlayerInput;
 
 pub struct ControlPlayerSystem;
 
@@ -11,20 +13,31 @@ impl ControlPlayerSystem {
     /// `(Option<Side>, Option<Side>)`
     /// representing if their is a solid collision on the x axis (horizontally, left/right)
     /// or on the y axis (vertically, top/bottom), and which side is in collision there.
+    /// `OneWay` platforms only count when the player is standing on them (bottom side).
     fn is_touching_solids_on_sides_horizontally_or_vertically<'a>(
         &self,
         entities: &Entities,
         collision: &Collision,
         collisions: &ReadStorage<'a, Collision>,
         solids: &ReadStorage<'a, Solid>,
+        one_ways: &ReadStorage<'a, OneWay>,
     ) -> (Option<Side>, Option<Side>) {
         let mut touching_horizontally_side = None;
         let mut touching_vertically_side = None;
         if collision.in_collision() {
-            for (other_entity, _, _) in (entities, collisions, solids).join() {
+            for (other_entity, _, _, one_way_opt) in
+                (entities, collisions, solids, one_ways.maybe()).join()
+            {
                 if let Some(colliding_with) =
                     collision.collision_with(other_entity.id())
                 {
+                    // One-way platforms only block from above
+                    if one_way_opt.is_some() {
+                        match colliding_with.side {
+                            Side::Bottom => (),
+                            _ => continue,
+                        }
+                    }
                     match colliding_with.side {
                         Side::Top | Side::Bottom => {
                             touching_vertically_side = Some(colliding_with.side)
@@ -46,6 +59,99 @@ impl ControlPlayerSystem {
         (touching_horizontally_side, touching_vertically_side)
     }
 
+    /// Returns the id of the solid entity, which the player is standing on
+    /// (colliding with on their bottom side), if any.
+    fn solid_below<'a>(
+        &self,
+        entities: &Entities,
+        collision: &Collision,
+        solids: &ReadStorage<'a, Solid>,
+    ) -> Option<Index> {
+        (entities, solids)
+            .join()
+            .map(|(entity, _)| entity.id())
+            .find(|id| {
+                if let Some(colliding_with) = collision.collision_with(*id) {
+                    if let Side::Bottom = colliding_with.side {
+                        return true;
+                    }
+                }
+                false
+            })
+    }
+
+    /// Returns the distance from the player's bottom edge down to the top edge
+    /// of the closest solid or `OneWay` platform below them, if there is one.
+    /// The platform the player is dropping through is ignored.
+    fn distance_to_ground<'a>(
+        &self,
+        entities: &Entities<'a>,
+        player_entity: Entity,
+        player: &Player,
+        (transforms, sizes, solids, one_ways): (
+            &ReadStorage<'a, Transform>,
+            &ReadStorage<'a, Size>,
+            &ReadStorage<'a, Solid>,
+            &ReadStorage<'a, OneWay>,
+        ),
+    ) -> Option<f32> {
+        let (player_x, player_y) =
+            transforms.get(player_entity).map(|transform| {
+                (transform.translation().x, transform.translation().y)
+            })?;
+        let player_size = sizes.get(player_entity)?;
+        let bottom = player_y - player_size.h * 0.5;
+
+        (
+            entities,
+            transforms,
+            sizes,
+            solids.maybe(),
+            one_ways.maybe(),
+        )
+            .join()
+            .filter(|(entity, _, _, solid_opt, one_way_opt)| {
+                *entity != player_entity
+                    && Some(entity.id()) != player.dropping_through
+                    && (solid_opt.is_some() || one_way_opt.is_some())
+            })
+            .filter(|(_, transform, size, _, _)| {
+                (transform.translation().x - player_x).abs()
+                    < (size.w + player_size.w) * 0.5
+            })
+            .map(|(_, transform, size, _, _)| {
+                bottom - (transform.translation().y + size.h * 0.5)
+            })
+            .filter(|distance| *distance >= 0.0)
+            .fold(None, |closest: Option<f32>, distance| {
+                Some(closest.map_or(distance, |closest| closest.min(distance)))
+            })
+    }
+
+    /// Drop through the `OneWay` platform the player is standing on,
+    /// when pressing down. The `OneWaySystem` makes it passable until the player is below it.
+    fn handle_drop_through<'a>(
+        &self,
+        entities: &Entities<'a>,
+        settings: &Settings,
+        input: &PlayerInput,
+        player: &mut Player,
+        one_ways: &ReadStorage<'a, OneWay>,
+    ) {
+        if settings.input.analog_scale(input.y) >= 0.0 {
+            return;
+        }
+        if let Some(standing_on) = player.standing_on {
+            if one_ways.contains(entities.entity(standing_on)) {
+                player.dropping_through = Some(standing_on);
+                player.standing_on = None;
+                player.is_in_air = true;
+                // No coyote jump after dropping through
+                player.coyote_timer = 0.0;
+            }
+        }
+    }
+
     fn handle_jump_recharge<'a>(
         &self,
         entities: &Entities<'a>,
@@ -67,7 +173,7 @@ impl ControlPlayerSystem {
     fn handle_wall_cling(
         &self,
         settings: &Settings,
-        input_manager: &Read<InputManager>,
+        input: &PlayerInput,
         player: &mut Player,
         velocity: &mut Velocity,
         (touching_horizontally_side, touching_vertically_side): (
@@ -93,22 +199,30 @@ impl ControlPlayerSystem {
                     velocity.y = slide_strength;
                 }
                 // Wall Jump
-                if input_manager.is_pressed("player_jump") {
-                    if !player.is_jump_button_down {
+                if input.jump {
+                    if !player.is_jump_button_down
+                        && settings.player.wall_jump.is_enabled
+                    {
+                        let strength = settings.player.wall_jump.strength;
                         if velocity.y < 0.0 {
                             velocity.y = 0.0;
                         }
-                        velocity.y += settings.player.jump_strength;
-                        // TODO: Have separate `player_wall_jump_strength` setting
+                        velocity.y += strength.1;
                         match side_hor {
-                            Side::Left => {
-                                velocity.x += settings.player.jump_strength
-                            }
-                            Side::Right => {
-                                velocity.x -= settings.player.jump_strength
-                            }
+                            Side::Left => velocity.x += strength.0,
+                            Side::Right => velocity.x -= strength.0,
                             _ => (),
                         }
+                        // Ignore horizontal input for a moment
+                        if let Some(lockout_ms) =
+                            settings.player.wall_jump.input_lockout_ms
+                        {
+                            player.wall_jump_lockout_timer =
+                                lockout_ms as f32 / 1000.0;
+                        }
+                        // Don't execute a buffered jump after this wall jump
+                        player.jump_buffer_timer = 0.0;
+                        player.coyote_timer = 0.0;
                     }
                     player.is_jump_button_down = true;
                 } else {
@@ -119,6 +233,8 @@ impl ControlPlayerSystem {
     }
 
     /// Handle some specifics when player is standing on solid ground vs when they are in air.
+    /// `solid_below` is the entity below the player, which they are standing on
+    /// when touching a solid with their bottom side.
     fn handle_on_ground_and_in_air(
         &self,
         player: &mut Player,
@@ -127,12 +243,15 @@ impl ControlPlayerSystem {
             Option<Side>,
             Option<Side>,
         ),
+        solid_below: Option<Index>,
     ) {
         player.is_in_air = true;
+        player.standing_on = None;
         if let Some(side_vert) = touching_vertically_side {
             if let Side::Bottom = side_vert {
                 // Standing on ground
                 player.is_in_air = false;
+                player.standing_on = solid_below;
             }
             // Reset y velocity to 0
             if match side_vert {
@@ -145,97 +264,156 @@ impl ControlPlayerSystem {
         }
     }
 
-    /// Move player left/right, if necessary
+    /// Move player left/right, if necessary.
+    /// Horizontal input is ignored for a moment after a wall jump.
     fn handle_move(
         &self,
         dt: f32,
         settings: &Settings,
-        input: &Read<InputHandler<String, String>>,
-        player: &Player,
+        input: &PlayerInput,
+        player: &mut Player,
         velocity: &mut Velocity,
         mut decr_velocity_opt: Option<&mut DecreaseVelocity>,
     ) {
         use crate::settings::SettingsPlayerQuickTurnaround as QTA;
 
-        // Move left/right, on X axis
-        if let Some(x) = input.axis_value("player_x") {
-            let x = x as f32;
-            if x != 0.0 {
-                let turned_around = x.signum() != velocity.x.signum();
-                if turned_around {
-                    // Quick turnaround, when on ground
-                    let qta_setting = if player.on_ground() {
-                        settings.player.quick_turnaround
-                    // Quick turnaround, when in air
-                    } else {
-                        settings.player.air_quick_turnaround
-                    };
-                    match qta_setting {
-                        QTA::ResetVelocity => velocity.x = 0.0,
-                        QTA::InvertVelocity => velocity.x *= -1.0,
-                        _ => (),
-                    }
+        // Wall jump input lockout; keep the wall jump's momentum meanwhile
+        if player.wall_jump_lockout_timer > 0.0 {
+            player.wall_jump_lockout_timer =
+                (player.wall_jump_lockout_timer - dt).max(0.0);
+            if let Some(decr) = decr_velocity_opt.as_mut() {
+                if velocity.x > 0.0 {
+                    decr.dont_decrease_x_when_pos();
+                } else if velocity.x < 0.0 {
+                    decr.dont_decrease_x_when_neg();
+                }
+            }
+            return;
+        }
+
+        // Move left/right, on X axis; analog input accelerates proportionally
+        let x = settings.input.analog_scale(input.x);
+        if x != 0.0 {
+            let turned_around = x.signum() != velocity.x.signum();
+            if turned_around {
+                // Quick turnaround, when on ground
+                let qta_setting = if player.on_ground() {
+                    settings.player.quick_turnaround
+                // Quick turnaround, when in air
+                } else {
+                    settings.player.air_quick_turnaround
+                };
+                match qta_setting {
+                    QTA::ResetVelocity => velocity.x = 0.0,
+                    QTA::InvertVelocity => velocity.x *= -1.0,
+                    _ => (),
+                }
+            }
+            velocity.x += (player.current_acceleration().0 * dt) * x;
+            if let Some(decr) = decr_velocity_opt.as_mut() {
+                if x > 0.0 {
+                    decr.dont_decrease_x_when_pos();
+                } else if x < 0.0 {
+                    decr.dont_decrease_x_when_neg();
                 }
-                velocity.x += (player.current_acceleration().0 * dt)
-                    * (x as f32).signum();
-                decr_velocity_opt.as_mut().map(|decr| {
-                    if x > 0.0 {
-                        decr.dont_decrease_x_when_pos();
-                    } else if x < 0.0 {
-                        decr.dont_decrease_x_when_neg();
-                    }
-                });
             }
         }
     }
 
     /// Handle player jumping. Regular and double jumps.
+    /// Regular jumps are also possible shortly after leaving the ground (coyote time),
+    /// and jump presses shortly before landing are buffered.
+    /// A press in the air is only spent on the double jump, if the player won't land
+    /// within the jump buffer time; otherwise it stays buffered, and becomes a ground jump
+    /// on landing. `ground_distance` is the distance down to the ground, if there is any.
     fn handle_jump(
         &self,
+        dt: f32,
         settings: &Settings,
-        input_manager: &InputManager,
+        input: &PlayerInput,
         player: &mut Player,
         velocity: &mut Velocity,
         gravity_opt: &mut Option<&mut Gravity>,
-        (audio_handler, audio_source, audio_output): (
+        ground_distance: Option<f32>,
+        (audio_handler, audio_source, audio_output_opt): (
             &AudioHandles,
             &AssetStorage<Source>,
-            &Output,
+            Option<&Output>,
         ),
     ) {
-        let is_jump_down = input_manager.is_pressed("player_jump");
-        let should_jump = (player.on_ground()  // Is standing on ground
-                    || (settings.player.is_double_jump_enabled  // Or has double jump available
-                        && !player.has_double_jumped))
-                    && is_jump_down  // And jump button is currently down
-                    && !player.is_jump_button_down; // And jump button has not already been down
+        let is_jump_down = input.jump;
+        let is_jump_pressed = is_jump_down && !player.is_jump_button_down; // Jump button was pressed this frame
+
+        // Coyote time
+        if player.on_ground() {
+            player.coyote_timer =
+                settings.player.coyote_time_ms as f32 / 1000.0;
+        } else {
+            player.coyote_timer = (player.coyote_timer - dt).max(0.0);
+        }
+        // Jump buffer
+        if is_jump_pressed {
+            player.jump_buffer_timer =
+                settings.player.jump_buffer_ms as f32 / 1000.0;
+        } else {
+            player.jump_buffer_timer = (player.jump_buffer_timer - dt).max(0.0);
+        }
+
+        let can_ground_jump = player.on_ground() || player.coyote_timer > 0.0;
+        let can_double_jump =
+            settings.player.is_double_jump_enabled && !player.has_double_jumped;
+        // Keep a press shortly before landing buffered for the ground jump
+        let will_land_in_buffer_time = !can_ground_jump
+            && ground_distance
+                .map(|distance| {
+                    will_fall_distance(
+                        distance,
+                        velocity.y,
+                        gravity_opt.as_ref().map(|gravity| gravity.y),
+                        player.current_max_velocity().1,
+                        player.jump_buffer_timer,
+                        dt,
+                    )
+                })
+                .unwrap_or(false);
+        let should_jump = (can_ground_jump  // Is standing on ground, or has just left it
+                    && (is_jump_pressed  // And jump button was just pressed
+                        || player.jump_buffer_timer > 0.0))  // Or was pressed shortly before landing
+                    || (can_double_jump  // Or has double jump available
+                        && is_jump_pressed
+                        && !will_land_in_buffer_time); // And won't land before the buffer runs out
         if should_jump {
-            audio_handler.play_with(
-                "player_jump",
-                audio_source,
-                audio_output,
-                None,
-            );
+            // There is no audio output in headless simulations
+            if let Some(audio_output) = audio_output_opt {
+                audio_handler.play_with(
+                    "player_jump",
+                    audio_source,
+                    audio_output,
+                    None,
+                );
+            }
 
-            player.has_double_jumped = player.in_air();
+            player.has_double_jumped = !can_ground_jump;
+            player.coyote_timer = 0.0;
+            player.jump_buffer_timer = 0.0;
             if velocity.y < 0.0 {
                 velocity.y = 0.0;
             }
             velocity.y += settings.player.jump_strength;
-            gravity_opt.as_mut().map(|gravity| {
+            if let Some(gravity) = gravity_opt.as_mut() {
                 gravity.x = settings.player.jump_gravity.0;
                 gravity.y = settings.player.jump_gravity.1;
-            });
+            }
         } else if !is_jump_down {
             let decr_jump_strength = settings.player.jump_strength * 0.25;
             if velocity.y > decr_jump_strength {
                 velocity.y =
                     (velocity.y - decr_jump_strength).max(decr_jump_strength);
             }
-            gravity_opt.as_mut().map(|gravity| {
+            if let Some(gravity) = gravity_opt.as_mut() {
                 gravity.x = settings.player.gravity.0;
                 gravity.y = settings.player.gravity.1;
-            });
+            }
         }
         player.is_jump_button_down = is_jump_down;
 
@@ -248,12 +426,12 @@ impl ControlPlayerSystem {
     /// Increase max velocity when holding down run button.
     fn handle_run(
         &self,
-        input_manager: &InputManager,
+        input: &PlayerInput,
         player: &mut Player,
         max_velocity_opt: &mut Option<&mut MaxVelocity>,
     ) {
-        let is_run_down = input_manager.is_pressed("player_run");
-        max_velocity_opt.as_mut().map(|max_vel| {
+        let is_run_down = input.run;
+        if let Some(max_vel) = max_velocity_opt.as_mut() {
             if is_run_down && !player.is_run_button_down {
                 // Start running
                 max_vel.x = player.run_max_velocity.0;
@@ -263,7 +441,7 @@ impl ControlPlayerSystem {
                 max_vel.x = player.max_velocity.0;
                 max_vel.y = player.max_velocity.1;
             }
-        });
+        }
         player.is_run_button_down = is_run_down;
     }
 }
@@ -272,15 +450,17 @@ impl<'a> System<'a> for ControlPlayerSystem {
     type SystemData = (
         Entities<'a>,
         ReadExpect<'a, Settings>,
-        ReadExpect<'a, Output>,
-        ReadExpect<'a, AudioHandles>,
+        Option<Read<'a, Output>>,
+        Read<'a, AudioHandles>,
         Read<'a, AssetStorage<Source>>,
         Read<'a, Time>,
-        Read<'a, InputHandler<String, String>>,
-        Read<'a, InputManager>,
+        Read<'a, PlayerInput>,
         ReadStorage<'a, Collision>,
         ReadStorage<'a, Solid>,
         ReadStorage<'a, JumpRecharge>,
+        ReadStorage<'a, OneWay>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Size>,
         WriteStorage<'a, Player>,
         WriteStorage<'a, Velocity>,
         WriteStorage<'a, MaxVelocity>,
@@ -297,11 +477,13 @@ impl<'a> System<'a> for ControlPlayerSystem {
             audio_handler,
             audio_source,
             time,
-            input_handler,
-            input_manager,
+            input,
             collisions,
             solids,
             jump_recharges,
+            one_ways,
+            transforms,
+            sizes,
             mut players,
             mut velocities,
             mut max_velocities,
@@ -311,6 +493,7 @@ impl<'a> System<'a> for ControlPlayerSystem {
     ) {
         let dt = time.delta_seconds();
         for (
+            entity,
             mut player,
             mut velocity,
             mut max_velocity_opt,
@@ -318,6 +501,7 @@ impl<'a> System<'a> for ControlPlayerSystem {
             collision,
             mut gravity_opt,
         ) in (
+            &entities,
             &mut players,
             &mut velocities,
             (&mut max_velocities).maybe(),
@@ -327,6 +511,11 @@ impl<'a> System<'a> for ControlPlayerSystem {
         )
             .join()
         {
+            // Dead players can't move, see `DeathSystem`
+            if player.is_dead() {
+                continue;
+            }
+
             // Check if the player is touching solids, and on which sides.
             let (touching_horizontally_side, touching_vertically_side) = self
                 .is_touching_solids_on_sides_horizontally_or_vertically(
@@ -334,6 +523,7 @@ impl<'a> System<'a> for ControlPlayerSystem {
                     &collision,
                     &collisions,
                     &solids,
+                    &one_ways,
                 );
 
             // Check if the player has collided with a JumpRecharge entity.
@@ -349,42 +539,95 @@ impl<'a> System<'a> for ControlPlayerSystem {
             // (constant velocity (for slow slide), wall jump, etc.)
             self.handle_wall_cling(
                 &settings,
-                &input_manager,
+                &input,
                 &mut player,
                 &mut velocity,
                 (touching_horizontally_side, touching_vertically_side),
             );
 
             // Handle some specifics for when player is on a solid ground vs when they are in air.
-            // (Resetting y velocity when on ground, etc.)
+            // (Resetting y velocity when on ground, remembering the ground for moving platforms, etc.)
             self.handle_on_ground_and_in_air(
                 &mut player,
                 &mut velocity,
                 (touching_horizontally_side, touching_vertically_side),
+                self.solid_below(&entities, &collision, &solids),
+            );
+
+            // Drop through one-way platforms, when pressing down
+            self.handle_drop_through(
+                &entities,
+                &settings,
+                &input,
+                &mut player,
+                &one_ways,
             );
 
             // Move left/right
             self.handle_move(
                 dt,
                 &settings,
-                &input_handler,
-                &player,
+                &input,
+                &mut player,
                 &mut velocity,
                 decr_velocity_opt,
             );
 
             // Regular and wall jumping
+            let ground_distance = self.distance_to_ground(
+                &entities,
+                entity,
+                &player,
+                (&transforms, &sizes, &solids, &one_ways),
+            );
             self.handle_jump(
+                dt,
                 &settings,
-                &input_manager,
+                &input,
                 &mut player,
                 &mut velocity,
                 &mut gravity_opt,
-                (&audio_handler, &audio_source, &audio_output),
+                ground_distance,
+                (
+                    &audio_handler,
+                    &audio_source,
+                    audio_output.as_ref().map(|output| &**output),
+                ),
             );
 
             // Running
-            self.handle_run(&input_manager, &mut player, &mut max_velocity_opt);
+            self.handle_run(&input, &mut player, &mut max_velocity_opt);
+        }
+    }
+}
+
+/// Returns `true` if an entity with the given vertical velocity, gravity and maximum
+/// velocity falls at least the given distance within the given seconds.
+/// Steps forward in ticks of `dt`, like the gravity, velocity limit and move systems do.
+fn will_fall_distance(
+    distance: f32,
+    velocity_y: f32,
+    gravity_y_opt: Option<f32>,
+    max_velocity_y_opt: Option<f32>,
+    seconds: f32,
+    dt: f32,
+) -> bool {
+    if dt <= 0.0 {
+        return false;
+    }
+    let mut velocity_y = velocity_y;
+    let mut fallen = 0.0;
+    let mut elapsed = 0.0;
+    while elapsed < seconds {
+        velocity_y += gravity_y_opt.unwrap_or(0.0) * dt;
+        if let Some(max) = max_velocity_y_opt {
+            velocity_y = velocity_y.max(-max);
+        }
+        fallen -= velocity_y * dt;
+        if fallen >= distance {
+            return true;
         }
+        elapsed += dt;
     }
+    false
 }
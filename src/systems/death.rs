@@ -0,0 +1,213 @@
+use amethyst::core::transform::Transform;
+use amethyst::renderer::Hidden;
+
+use super::system_prelude::*;
+use crate::map_bounds::MapBounds;
+
+/// While dead, the player is shown and hidden every this many seconds.
+const BLINK_INTERVAL: f32 = 0.1;
+
+/// Activates `Checkpoint`s the player enters, kills the player when they touch
+/// a `Hazard` or fall below the kill plane, and respawns them after a short delay.
+/// While dead, the player is frozen in place (no velocity or gravity) and blinks.
+/// Runs first, so the `ControlPlayerSystem` and `GravitySystem` see the frozen player.
+pub struct DeathSystem;
+
+impl DeathSystem {
+    /// Activates the checkpoint the player has just entered,
+    /// and makes its position the player's spawn point.
+    fn handle_checkpoints<'a>(
+        &self,
+        entities: &Entities<'a>,
+        player: &mut Player,
+        collision: &Collision,
+        checkpoints: &mut WriteStorage<'a, Checkpoint>,
+        transforms: &WriteStorage<'a, Transform>,
+    ) {
+        let entered = (entities, &*checkpoints, transforms)
+            .join()
+            .find(|(entity, checkpoint, _)| {
+                !checkpoint.is_active
+                    && collision
+                        .collision_with(entity.id())
+                        .map(|data| data.state.is_entering())
+                        .unwrap_or(false)
+            })
+            .map(|(entity, _, transform)| {
+                (
+                    entity.id(),
+                    (transform.translation().x, transform.translation().y),
+                )
+            });
+
+        if let Some((id, pos)) = entered {
+            for (entity, checkpoint) in (entities, checkpoints).join() {
+                checkpoint.is_active = entity.id() == id;
+            }
+            player.checkpoint = Some(id);
+            player.spawn_point = pos.into();
+        }
+    }
+
+    /// Returns `true` if the player is inside a `Hazard`, touches a `Solid` hazard
+    /// from any side (it can't be entered), or is below the kill plane.
+    /// Standing next to a hazard, which isn't solid, is safe.
+    fn should_die<'a>(
+        &self,
+        entities: &Entities<'a>,
+        collision: &Collision,
+        transform: &Transform,
+        hazards: &ReadStorage<'a, Hazard>,
+        solids: &ReadStorage<'a, Solid>,
+        kill_plane_opt: Option<f32>,
+    ) -> bool {
+        let is_touching_hazard = (entities, hazards, solids.maybe())
+            .join()
+            .any(|(entity, _, solid_opt)| {
+                collision
+                    .collision_with(entity.id())
+                    .map(|data| {
+                        (solid_opt.is_some() || data.side.is_inner())
+                            && (data.state.is_entering()
+                                || data.state.is_steady())
+                    })
+                    .unwrap_or(false)
+            });
+        let is_below_kill_plane = kill_plane_opt
+            .map(|kill_plane| transform.translation().y < kill_plane)
+            .unwrap_or(false);
+        is_touching_hazard || is_below_kill_plane
+    }
+
+    /// Reset the player to their spawn point, with their double jump recharged.
+    fn respawn(
+        &self,
+        settings: &Settings,
+        player: &mut Player,
+        transform: &mut Transform,
+        velocity: &mut Velocity,
+        gravity: &mut Gravity,
+    ) {
+        transform.set_x(player.spawn_point.0);
+        transform.set_y(player.spawn_point.1);
+        velocity.x = 0.0;
+        velocity.y = 0.0;
+        gravity.x = settings.player.gravity.0;
+        gravity.y = settings.player.gravity.1;
+        player.has_double_jumped = false;
+        player.coyote_timer = 0.0;
+        player.jump_buffer_timer = 0.0;
+        player.wall_jump_lockout_timer = 0.0;
+        player.standing_on = None;
+        player.dropping_through = None;
+    }
+}
+
+impl<'a> System<'a> for DeathSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Settings>,
+        Read<'a, Time>,
+        Option<Read<'a, MapBounds>>,
+        ReadStorage<'a, Collision>,
+        ReadStorage<'a, Hazard>,
+        ReadStorage<'a, Solid>,
+        WriteStorage<'a, Checkpoint>,
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, Gravity>,
+        WriteStorage<'a, Hidden>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            settings,
+            time,
+            map_bounds,
+            collisions,
+            hazards,
+            solids,
+            mut checkpoints,
+            mut players,
+            mut transforms,
+            mut velocities,
+            mut gravities,
+            mut hiddens,
+        ): Self::SystemData,
+    ) {
+        let dt = time.delta_seconds();
+        let kill_plane_opt = map_bounds.as_ref().map(|bounds| {
+            bounds.bottom - settings.player.death.kill_plane_distance
+        });
+
+        for (entity, player, collision) in
+            (&entities, &mut players, &collisions).join()
+        {
+            if let Some(death_timer) = player.death_timer {
+                let death_timer = death_timer - dt;
+                let is_respawning = death_timer <= 0.0;
+                if let (Some(transform), Some(velocity), Some(gravity)) = (
+                    transforms.get_mut(entity),
+                    velocities.get_mut(entity),
+                    gravities.get_mut(entity),
+                ) {
+                    if is_respawning {
+                        self.respawn(
+                            &settings, player, transform, velocity, gravity,
+                        );
+                    } else {
+                        // Frozen in place
+                        velocity.x = 0.0;
+                        velocity.y = 0.0;
+                        gravity.x = 0.0;
+                        gravity.y = 0.0;
+                    }
+                }
+                if is_respawning {
+                    player.death_timer = None;
+                    hiddens.remove(entity);
+                } else {
+                    player.death_timer = Some(death_timer);
+                    // Blink
+                    if (death_timer / BLINK_INTERVAL) as u32 % 2 == 0 {
+                        hiddens.insert(entity, Hidden).unwrap();
+                    } else {
+                        hiddens.remove(entity);
+                    }
+                }
+                continue;
+            }
+
+            self.handle_checkpoints(
+                &entities,
+                player,
+                collision,
+                &mut checkpoints,
+                &transforms,
+            );
+
+            let should_die = transforms
+                .get(entity)
+                .map(|transform| {
+                    self.should_die(
+                        &entities,
+                        collision,
+                        transform,
+                        &hazards,
+                        &solids,
+                        kill_plane_opt,
+                    )
+                })
+                .unwrap_or(false);
+            if should_die {
+                player.death_timer = Some(
+                    settings.player.death.respawn_delay_ms as f32 / 1000.0,
+                );
+                player.standing_on = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use amethyst::core::transform::Transform;
+use amethyst::renderer::Flipped;
+
+use super::system_prelude::*;
+use crate::ghost::{GhostFrame, Ghosts};
+use crate::level_timer::{LevelTimer, TimerState};
+
+/// Records the player's position, sprite and facing every tick while the level timer runs,
+/// and moves `Ghost` entities along the best run, in sync with the level timer.
+/// Runs after the player has moved.
+pub struct GhostSystem;
+
+impl<'a> System<'a> for GhostSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, LevelTimer>,
+        Write<'a, Ghosts>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Ghost>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, SpriteRender>,
+        WriteStorage<'a, Flipped>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            level_timer,
+            mut ghosts,
+            players,
+            ghost_markers,
+            mut transforms,
+            mut sprite_renders,
+            mut flippeds,
+        ): Self::SystemData,
+    ) {
+        // Record
+        if let TimerState::Running = level_timer.state() {
+            let frame_opt = (
+                &players,
+                &transforms,
+                sprite_renders.maybe(),
+                flippeds.maybe(),
+            )
+                .join()
+                .next()
+                .map(
+                    |(_, transform, sprite_render_opt, flipped_opt)| {
+                        GhostFrame {
+                            pos:     (
+                                transform.translation().x,
+                                transform.translation().y,
+                            ),
+                            sprite:  sprite_render_opt
+                                .map(|sprite_render| {
+                                    sprite_render.sprite_number
+                                })
+                                .unwrap_or(0),
+                            flipped: flipped_opt.is_some(),
+                        }
+                    },
+                );
+            if let Some(frame) = frame_opt {
+                ghosts.recording.frames.push(frame);
+            }
+        }
+
+        // Play back
+        let frame_opt = ghosts
+            .best
+            .as_ref()
+            .and_then(|best| best.frame_at(level_timer.ticks()));
+        if let Some(frame) = frame_opt {
+            for (entity, _, transform, sprite_render_opt) in (
+                &entities,
+                &ghost_markers,
+                &mut transforms,
+                (&mut sprite_renders).maybe(),
+            )
+                .join()
+            {
+                transform.set_x(frame.pos.0);
+                transform.set_y(frame.pos.1);
+                if let Some(sprite_render) = sprite_render_opt {
+                    sprite_render.sprite_number = frame.sprite;
+                }
+                if frame.flipped {
+                    flippeds.insert(entity, Flipped::Horizontal).unwrap();
+                } else {
+                    flippeds.remove(entity);
+                }
+            }
+        }
+    }
+}
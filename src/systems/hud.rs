@@ -0,0 +1,89 @@
+use amethyst::ui::{UiText, UiTransform};
+
+use super::system_prelude::*;
+use crate::level_timer::{format_delta, format_time, LevelTimer};
+use crate::score::Score;
+
+/// Color of the timer's delta, when faster than the personal best.
+const AHEAD_COLOR: [f32; 4] = [0.2, 1.0, 0.2, 1.0];
+/// Color of the timer's delta, when slower than the personal best.
+const BEHIND_COLOR: [f32; 4] = [1.0, 0.2, 0.2, 1.0];
+
+/// Updates the HUD's texts (see `resources/ui/hud.ron`):
+/// the level's collected items, the level timer with its delta to the personal best,
+/// and if double jump is available.
+pub struct HudSystem;
+
+impl<'a> System<'a> for HudSystem {
+    type SystemData = (
+        ReadExpect<'a, Settings>,
+        Read<'a, Score>,
+        Read<'a, LevelTimer>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Collectible>,
+        ReadStorage<'a, UiTransform>,
+        WriteStorage<'a, UiText>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            settings,
+            score,
+            level_timer,
+            players,
+            collectibles,
+            ui_transforms,
+            mut ui_texts,
+        ): Self::SystemData,
+    ) {
+        // Collected items are deleted, ghosts are already in the tally
+        let collected = score
+            .tally()
+            .map(|tally| tally.collected.len())
+            .unwrap_or(0);
+        let remaining = collectibles
+            .join()
+            .filter(|collectible| !collectible.is_ghost)
+            .count();
+        let collected_text =
+            format!("ITEMS: {}/{}", collected, collected + remaining);
+
+        let timer_text =
+            format!("TIME: {}", format_time(level_timer.seconds()));
+        let delta_opt = level_timer.delta_seconds();
+        let delta_text = delta_opt.map(format_delta).unwrap_or_default();
+        let delta_color = match delta_opt {
+            Some(delta) if delta < 0.0 => AHEAD_COLOR,
+            _ => BEHIND_COLOR,
+        };
+
+        let double_jump_text = match players.join().next() {
+            Some(player)
+                if settings.player.is_double_jump_enabled
+                    && !player.has_double_jumped =>
+            {
+                "DOUBLE JUMP: READY"
+            }
+            Some(_) if settings.player.is_double_jump_enabled => {
+                "DOUBLE JUMP: USED"
+            }
+            _ => "",
+        };
+
+        for (ui_transform, ui_text) in (&ui_transforms, &mut ui_texts).join() {
+            match ui_transform.id.as_str() {
+                "hud_collected" => ui_text.text = collected_text.clone(),
+                "hud_timer" => ui_text.text = timer_text.clone(),
+                "hud_delta" => {
+                    ui_text.text = delta_text.clone();
+                    ui_text.color = delta_color;
+                }
+                "hud_double_jump" => {
+                    ui_text.text = double_jump_text.to_string()
+                }
+                _ => (),
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use super::system_prelude::*;
+use crate::level_exit::LevelExitReached;
+
+/// Sets the `LevelExitReached` resource, when the player has just entered a `LevelExit`.
+pub struct LevelExitSystem;
+
+impl<'a> System<'a> for LevelExitSystem {
+    type SystemData = (
+        Entities<'a>,
+        Write<'a, LevelExitReached>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Collision>,
+        ReadStorage<'a, LevelExit>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut level_exit_reached,
+            players,
+            collisions,
+            level_exits,
+        ): Self::SystemData,
+    ) {
+        for (_, collision) in (&players, &collisions).join() {
+            for (entity, level_exit) in (&entities, &level_exits).join() {
+                if let Some(coll_data) = collision.collision_with(entity.id()) {
+                    if coll_data.state.is_entering() {
+                        level_exit_reached.reach(&level_exit.level);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use super::system_prelude::*;
+use crate::level_timer::LevelTimer;
+use crate::player_input::PlayerInput;
+
+/// Starts the `LevelTimer` with the player's first input (ignoring stick drift),
+/// counts the physics ticks, and splits when the player reaches a checkpoint.
+pub struct LevelTimerSystem;
+
+impl<'a> System<'a> for LevelTimerSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Settings>,
+        Read<'a, PlayerInput>,
+        Write<'a, LevelTimer>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Checkpoint>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            settings,
+            input,
+            mut level_timer,
+            players,
+            checkpoints,
+        ): Self::SystemData,
+    ) {
+        if input.is_active(&settings.input) {
+            level_timer.start();
+        }
+        level_timer.tick(settings.physics.tick_rate);
+        for player in players.join() {
+            if let Some(checkpoint) = player
+                .checkpoint
+                .and_then(|index| checkpoints.get(entities.entity(index)))
+            {
+                level_timer.split(&checkpoint.id);
+            }
+        }
+    }
+}
@@ -1,13 +1,40 @@
+mod camera_bounds;
+mod collect;
 mod control_player;
+mod death;
 mod debug;
+mod ghost;
+mod hud;
+mod level_exit;
+mod level_timer;
+mod move_platforms;
+mod one_way;
+mod player_animation;
+mod reload_settings;
+mod replay;
 pub mod ui_event_handlers;
+mod update_player_input;
 
 pub mod prelude {
     pub use deathframe::systems::prelude::*;
 
     pub use super::ui_event_handlers;
+    pub use super::CameraBoundsSystem;
+    pub use super::CollectSystem;
     pub use super::ControlPlayerSystem;
+    pub use super::DeathSystem;
     pub use super::DebugSystem;
+    pub use super::FinishMovingPlatformsSystem;
+    pub use super::GhostSystem;
+    pub use super::HudSystem;
+    pub use super::LevelExitSystem;
+    pub use super::LevelTimerSystem;
+    pub use super::MovePlatformsSystem;
+    pub use super::OneWaySystem;
+    pub use super::PlayerAnimationSystem;
+    pub use super::ReloadSettingsSystem;
+    pub use super::ReplaySystem;
+    pub use super::UpdatePlayerInputSystem;
 }
 
 mod system_prelude {
@@ -39,5 +66,18 @@ mod system_prelude {
     pub use crate::settings::prelude::*;
 }
 
+pub use camera_bounds::CameraBoundsSystem;
+pub use collect::CollectSystem;
 pub use control_player::ControlPlayerSystem;
+pub use death::DeathSystem;
 pub use debug::DebugSystem;
+pub use ghost::GhostSystem;
+pub use hud::HudSystem;
+pub use level_exit::LevelExitSystem;
+pub use level_timer::LevelTimerSystem;
+pub use move_platforms::{FinishMovingPlatformsSystem, MovePlatformsSystem};
+pub use one_way::OneWaySystem;
+pub use player_animation::PlayerAnimationSystem;
+pub use reload_settings::ReloadSettingsSystem;
+pub use replay::ReplaySystem;
+pub use update_player_input::UpdatePlayerInputSystem;
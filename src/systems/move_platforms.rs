@@ -0,0 +1,120 @@
+use amethyst::core::transform::Transform;
+
+use super::system_prelude::*;
+
+/// Moves `MovingPlatform` entities towards their next waypoint,
+/// and carries players standing on them (see `Player::standing_on`),
+/// by adding the platform's velocity to theirs, so the `MoveEntitiesSystem`
+/// moves them with collision checks. The `FinishMovingPlatformsSystem` removes it again.
+/// Platforms moving up are only moved after the players (see `MovingPlatform::pending`),
+/// so the players are never inside them, when they are moved.
+/// Runs after the `ControlPlayerSystem` and the `LimitVelocitiesSystem`,
+/// and before the `MoveEntitiesSystem`.
+pub struct MovePlatformsSystem;
+
+impl<'a> System<'a> for MovePlatformsSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, MovingPlatform>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            time,
+            mut players,
+            mut velocities,
+            mut platforms,
+            mut transforms,
+        ): Self::SystemData,
+    ) {
+        let dt = time.delta_seconds();
+
+        for (platform, transform) in (&mut platforms, &mut transforms).join() {
+            let target = if let Some(target) = platform.next_waypoint() {
+                target
+            } else {
+                platform.velocity = (0.0, 0.0).into();
+                continue;
+            };
+            let pos = (transform.translation().x, transform.translation().y);
+            let diff = (target.0 - pos.0, target.1 - pos.1);
+            let distance = (diff.0 * diff.0 + diff.1 * diff.1).sqrt();
+            let step = platform.speed * dt;
+
+            let delta = if distance <= step {
+                platform.advance();
+                diff
+            } else {
+                (diff.0 / distance * step, diff.1 / distance * step)
+            };
+
+            if delta.1 > 0.0 {
+                platform.pending = delta.into();
+            } else {
+                transform.translate_xyz(delta.0, delta.1, 0.0);
+            }
+            platform.velocity = if dt > 0.0 {
+                (delta.0 / dt, delta.1 / dt).into()
+            } else {
+                (0.0, 0.0).into()
+            };
+        }
+
+        // Carry players standing on top
+        for (player, velocity) in (&mut players, &mut velocities).join() {
+            let carried = player
+                .standing_on
+                .and_then(|standing_on| {
+                    platforms.get(entities.entity(standing_on))
+                })
+                .map(|platform| platform.velocity.clone());
+            if let Some(carried) = carried {
+                velocity.x += carried.0;
+                velocity.y += carried.1;
+                player.carried_velocity = carried;
+            }
+        }
+    }
+}
+
+/// Moves `MovingPlatform` entities moving up, after the players have moved,
+/// and removes the platforms' velocity from the players they carried.
+/// Runs after the `MoveEntitiesSystem`, and before the `CollisionSystem`.
+pub struct FinishMovingPlatformsSystem;
+
+impl<'a> System<'a> for FinishMovingPlatformsSystem {
+    type SystemData = (
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, Velocity>,
+        WriteStorage<'a, MovingPlatform>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut players,
+            mut velocities,
+            mut platforms,
+            mut transforms,
+        ): Self::SystemData,
+    ) {
+        for (platform, transform) in (&mut platforms, &mut transforms).join() {
+            let pending = platform.pending.clone();
+            transform.translate_xyz(pending.0, pending.1, 0.0);
+            platform.pending = (0.0, 0.0).into();
+        }
+
+        for (player, velocity) in (&mut players, &mut velocities).join() {
+            velocity.x -= player.carried_velocity.0;
+            velocity.y -= player.carried_velocity.1;
+            player.carried_velocity = (0.0, 0.0).into();
+        }
+    }
+}
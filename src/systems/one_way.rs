@@ -0,0 +1,58 @@
+use amethyst::core::transform::Transform;
+
+use super::system_prelude::*;
+
+/// How far a player's bottom edge may be below a platform's top edge,
+/// to still count as being above it.
+const ABOVE_PADDING: f32 = 1.0;
+
+/// Makes `OneWay` platforms `Solid`, only while a player is above them,
+/// so they can be jumped through from below and landed on from above.
+/// Players dropping through a platform (see `Player::dropping_through`)
+/// are ignored, until they are below it.
+/// Runs before the `MoveEntitiesSystem`.
+pub struct OneWaySystem;
+
+impl<'a> System<'a> for OneWaySystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, OneWay>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Size>,
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, Solid>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, one_ways, transforms, sizes, mut players, mut solids): Self::SystemData,
+    ) {
+        for (entity, _, transform, size) in
+            (&entities, &one_ways, &transforms, &sizes).join()
+        {
+            let top = transform.translation().y + size.h * 0.5;
+            let mut is_solid = false;
+
+            for (player, player_transform, player_size) in
+                (&mut players, &transforms, &sizes).join()
+            {
+                let bottom =
+                    player_transform.translation().y - player_size.h * 0.5;
+                let is_above = bottom >= top - ABOVE_PADDING;
+                if player.dropping_through == Some(entity.id()) {
+                    if is_above {
+                        continue;
+                    }
+                    player.dropping_through = None;
+                }
+                is_solid = is_solid || is_above;
+            }
+
+            if is_solid && !solids.contains(entity) {
+                solids.insert(entity, Solid).unwrap();
+            } else if !is_solid && solids.contains(entity) {
+                solids.remove(entity);
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use amethyst::renderer::Flipped;
+
+use super::system_prelude::*;
+
+/// Below this horizontal speed, the player counts as standing still.
+const MOVING_THRESHOLD: f32 = 10.0;
+
+/// Chooses the player's animation clip from their state and velocity,
+/// advances it, and flips the sprite to face the direction they're moving in.
+pub struct PlayerAnimationSystem;
+
+impl<'a> System<'a> for PlayerAnimationSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Velocity>,
+        WriteStorage<'a, PlayerAnimation>,
+        WriteStorage<'a, SpriteRender>,
+        WriteStorage<'a, Flipped>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            time,
+            players,
+            velocities,
+            mut player_animations,
+            mut sprite_renders,
+            mut flippeds,
+        ): Self::SystemData,
+    ) {
+        let delta_ms = time.delta_seconds() * 1000.0;
+
+        for (entity, player, velocity, animation, sprite_render) in (
+            &entities,
+            &players,
+            &velocities,
+            &mut player_animations,
+            &mut sprite_renders,
+        )
+            .join()
+        {
+            if velocity.x.abs() > MOVING_THRESHOLD {
+                animation.facing_left = velocity.x < 0.0;
+            }
+
+            let clip = clip_for(player, velocity);
+            animation.play(clip);
+
+            let speed = if clip == "run" && player.is_run_button_down {
+                animation.animations.run_button_speed
+            } else {
+                1.0
+            };
+            if let Some(sprite_id) = animation.update(delta_ms * speed) {
+                sprite_render.sprite_number = sprite_id;
+            }
+
+            if animation.facing_left {
+                flippeds.insert(entity, Flipped::Horizontal).unwrap();
+            } else {
+                flippeds.remove(entity);
+            }
+        }
+    }
+}
+
+/// Returns the name of the clip to play, for the player's state.
+fn clip_for(player: &Player, velocity: &Velocity) -> &'static str {
+    if player.in_air() {
+        if player.on_wall() && velocity.y <= 0.0 {
+            "wall_slide"
+        } else if velocity.y > 0.0 && player.has_double_jumped {
+            "double_jump"
+        } else if velocity.y > 0.0 {
+            "jump"
+        } else {
+            "fall"
+        }
+    } else if velocity.x.abs() > MOVING_THRESHOLD {
+        "run"
+    } else {
+        "idle"
+    }
+}
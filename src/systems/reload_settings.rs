@@ -0,0 +1,149 @@
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+
+use amethyst::renderer::{Camera as AmethystCamera, Projection};
+
+use super::system_prelude::*;
+
+const CHECK_EVERY_MS: u64 = 500;
+
+/// Watches the `settings.ron` file for changes, and reloads it when it was modified.
+/// The new values are applied to the `Settings` resource,
+/// and pushed into the existing player and camera components.
+pub struct ReloadSettingsSystem {
+    last_check:    Instant,
+    last_modified: Option<SystemTime>,
+}
+
+impl ReloadSettingsSystem {
+    /// Returns `true` if the settings file was modified since the last check.
+    /// If the file's modification time was unknown, it counts as modified.
+    fn was_modified(&mut self) -> bool {
+        match (self.last_modified, settings_modified()) {
+            (Some(last), Some(current)) if current > last => {
+                self.last_modified = Some(current);
+                true
+            }
+            (None, Some(current)) => {
+                self.last_modified = Some(current);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the settings file's modification time, if it can be read.
+fn settings_modified() -> Option<SystemTime> {
+    fs::metadata(settings_path())
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+impl<'a> System<'a> for ReloadSettingsSystem {
+    type SystemData = (
+        WriteExpect<'a, Settings>,
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, MaxVelocity>,
+        WriteStorage<'a, DecreaseVelocity>,
+        WriteStorage<'a, Gravity>,
+        WriteStorage<'a, Camera>,
+        WriteStorage<'a, AmethystCamera>,
+        WriteStorage<'a, Size>,
+        WriteStorage<'a, InnerSize>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut settings,
+            mut players,
+            mut max_velocities,
+            mut decr_velocities,
+            mut gravities,
+            mut cameras,
+            mut amethyst_cameras,
+            mut sizes,
+            mut inner_sizes,
+        ): Self::SystemData,
+    ) {
+        let now = Instant::now();
+        if now - self.last_check < Duration::from_millis(CHECK_EVERY_MS) {
+            return;
+        }
+        self.last_check = now;
+
+        if !self.was_modified() {
+            return;
+        }
+
+        match load_settings() {
+            Ok(new_settings) => *settings = new_settings,
+            Err(err) => {
+                eprintln!("{}\nKeeping previous settings.", err);
+                return;
+            }
+        }
+
+        // Player
+        for (player, max_velocity_opt, decr_velocity_opt, gravity_opt) in (
+            &mut players,
+            (&mut max_velocities).maybe(),
+            (&mut decr_velocities).maybe(),
+            (&mut gravities).maybe(),
+        )
+            .join()
+        {
+            player.acceleration = settings.player.acceleration;
+            player.run_acceleration = settings.player.run_acceleration;
+            player.max_velocity = settings.player.max_velocity;
+            player.run_max_velocity = settings.player.run_max_velocity;
+
+            if let Some(max_velocity) = max_velocity_opt {
+                let (x, y) = player.current_max_velocity();
+                max_velocity.x = x;
+                max_velocity.y = y;
+            }
+            if let Some(decr_velocity) = decr_velocity_opt {
+                *decr_velocity =
+                    DecreaseVelocity::from(settings.player.decr_velocity);
+            }
+            if let Some(gravity) = gravity_opt {
+                gravity.x = settings.player.gravity.0;
+                gravity.y = settings.player.gravity.1;
+            }
+        }
+
+        // Camera
+        for (camera, amethyst_camera, size, inner_size) in (
+            &mut cameras,
+            &mut amethyst_cameras,
+            &mut sizes,
+            &mut inner_sizes,
+        )
+            .join()
+        {
+            camera.base_speed = settings.camera.base_speed;
+            camera.deadzone = settings.camera.deadzone;
+            *amethyst_camera = AmethystCamera::from(Projection::orthographic(
+                0.0,                    // Left
+                settings.camera.size.0, // Right
+                0.0,                    // Bottom (!)
+                settings.camera.size.1, // Top    (!)
+            ));
+            *size = Size::from(settings.camera.size);
+            *inner_size = InnerSize(Size::from(settings.camera.inner_size));
+        }
+    }
+}
+
+/// The settings file's modification time is stored when the system is created,
+/// around when the settings are loaded, so any later change is reloaded.
+impl Default for ReloadSettingsSystem {
+    fn default() -> Self {
+        Self {
+            last_check:    Instant::now(),
+            last_modified: settings_modified(),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use super::system_prelude::*;
+use crate::player_input::PlayerInput;
+use crate::replay::{ReplayFrame, ReplayMode};
+
+/// Records the player's input every physics tick, or plays it back,
+/// depending on the `ReplayMode` resource.
+/// Runs first in the `PhysicsDispatcher`, before any gameplay systems.
+pub struct ReplaySystem;
+
+impl<'a> System<'a> for ReplaySystem {
+    type SystemData = (
+        Write<'a, ReplayMode>,
+        Write<'a, PlayerInput>,
+        Write<'a, Time>,
+    );
+
+    fn run(
+        &mut self,
+        (mut replay_mode, mut player_input, mut time): Self::SystemData,
+    ) {
+        let mut is_finished = false;
+
+        match &mut *replay_mode {
+            ReplayMode::Off => (),
+            ReplayMode::Record { replay, .. } => {
+                replay.frames.push(ReplayFrame {
+                    delta_seconds: time.delta_seconds(),
+                    input:         player_input.clone(),
+                });
+            }
+            ReplayMode::Playback { replay, frame } => {
+                if let Some(replay_frame) = replay.frames.get(*frame) {
+                    *player_input = replay_frame.input.clone();
+                    time.set_delta_seconds(replay_frame.delta_seconds);
+                    *frame += 1;
+                } else {
+                    is_finished = true;
+                }
+            }
+        }
+
+        if is_finished {
+            eprintln!("Replay finished, switching to live input");
+            *replay_mode = ReplayMode::Off;
+        }
+    }
+}
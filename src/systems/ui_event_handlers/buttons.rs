@@ -0,0 +1,72 @@
+use amethyst::ui::{UiEventType, UiText, UiTransform};
+
+use super::system_prelude::*;
+use crate::ui_buttons::UiButtons;
+
+const NORMAL_COLOR: [f32; 4] = [0.2, 0.2, 1.0, 1.0];
+const SELECTED_COLOR: [f32; 4] = [0.6, 0.6, 1.0, 1.0];
+
+/// Handles the buttons of the currently shown menu (see `UiButtons`).
+/// Hovering with the mouse or pressing `ui_up` / `ui_down` selects a button,
+/// clicking it or pressing `ui_select` presses it.
+/// The selected button's text is highlighted.
+pub struct ButtonsSystem {
+    reader_id: Option<ReaderId<UiEvent>>,
+}
+
+impl<'a> System<'a> for ButtonsSystem {
+    type SystemData = (
+        Write<'a, EventChannel<UiEvent>>,
+        Write<'a, UiButtons>,
+        Read<'a, InputManager>,
+        ReadStorage<'a, UiTransform>,
+        WriteStorage<'a, UiText>,
+    );
+
+    fn run(
+        &mut self,
+        (mut events, mut buttons, input_manager, ui_transforms, mut ui_texts): Self::SystemData,
+    ) {
+        let reader_id = self
+            .reader_id
+            .get_or_insert_with(|| events.register_reader());
+
+        // Mouse
+        for event in events.read(reader_id) {
+            if let Some(ui_transform) = ui_transforms.get(event.target) {
+                match event.event_type {
+                    UiEventType::HoverStart => buttons.select(&ui_transform.id),
+                    UiEventType::ClickStop => buttons.press(&ui_transform.id),
+                    _ => (),
+                }
+            }
+        }
+
+        // Keyboard
+        if input_manager.is_down("ui_down") {
+            buttons.select_next();
+        } else if input_manager.is_down("ui_up") {
+            buttons.select_previous();
+        }
+        if input_manager.is_down("ui_select") {
+            buttons.press_selected();
+        }
+
+        // Highlight selected button
+        for (ui_transform, ui_text) in (&ui_transforms, &mut ui_texts).join() {
+            if let Some(is_selected) = buttons.is_selected(&ui_transform.id) {
+                ui_text.color = if is_selected {
+                    SELECTED_COLOR
+                } else {
+                    NORMAL_COLOR
+                };
+            }
+        }
+    }
+}
+
+impl Default for ButtonsSystem {
+    fn default() -> Self {
+        Self { reader_id: None }
+    }
+}
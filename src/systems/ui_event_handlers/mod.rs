@@ -1,7 +1,7 @@
-mod pause;
+mod buttons;
 
 pub mod prelude {
-    pub use super::UIPauseSystem;
+    pub use super::UIButtonsSystem;
 }
 
 mod system_prelude {
@@ -11,4 +11,4 @@ mod system_prelude {
     pub use super::super::system_prelude::*;
 }
 
-pub use pause::PauseSystem as UIPauseSystem;
+pub use buttons::ButtonsSystem as UIButtonsSystem;
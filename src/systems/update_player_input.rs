@@ -0,0 +1,17 @@
+use super::system_prelude::*;
+use crate::player_input::PlayerInput;
+
+/// Reads the player's bindings from the live keyboard and controller input,
+/// and writes them to the `PlayerInput` resource.
+pub struct UpdatePlayerInputSystem;
+
+impl<'a> System<'a> for UpdatePlayerInputSystem {
+    type SystemData = (
+        Read<'a, InputHandler<String, String>>,
+        Write<'a, PlayerInput>,
+    );
+
+    fn run(&mut self, (input_handler, mut player_input): Self::SystemData) {
+        *player_input = PlayerInput::from_input_handler(&input_handler);
+    }
+}
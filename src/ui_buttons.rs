@@ -0,0 +1,97 @@
+/// What happens when a menu button is pressed.
+/// The state showing the menu decides how to handle it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UiAction {
+    Start,
+    LevelSelect,
+    Options,
+    Quit,
+    Resume,
+    RestartLevel,
+    QuitToMenu,
+    VolumeDown,
+    VolumeUp,
+    ToggleGhosts,
+    /// Capture a new key for the rebindable key binding with the given index,
+    /// see `key_bindings::REBINDABLE`.
+    Rebind(usize),
+    Back,
+}
+
+/// Resource with the buttons of the currently shown menu.
+/// Maps the buttons' `UiTransform` ids to actions, in navigation order.
+/// States set this when they show a menu; the `UIButtonsSystem` handles
+/// mouse and keyboard navigation, and stores the pressed button's action.
+#[derive(Debug, Clone, Default)]
+pub struct UiButtons {
+    buttons:  Vec<(String, UiAction)>,
+    selected: usize,
+    pressed:  Option<UiAction>,
+}
+
+impl UiButtons {
+    pub fn new<T: ToString>(buttons: Vec<(T, UiAction)>) -> Self {
+        Self {
+            buttons:  buttons
+                .into_iter()
+                .map(|(id, action)| (id.to_string(), action))
+                .collect(),
+            selected: 0,
+            pressed:  None,
+        }
+    }
+
+    /// Returns `Some(true)` if the button with the given id is selected,
+    /// `Some(false)` if it isn't, and `None` if it isn't one of the buttons.
+    pub fn is_selected(&self, id: &str) -> Option<bool> {
+        self.buttons
+            .iter()
+            .position(|(button_id, _)| button_id == id)
+            .map(|index| index == self.selected)
+    }
+
+    /// Select the button with the given id, if it is one of the buttons.
+    pub fn select(&mut self, id: &str) {
+        if let Some(index) = self
+            .buttons
+            .iter()
+            .position(|(button_id, _)| button_id == id)
+        {
+            self.selected = index;
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.buttons.is_empty() {
+            self.selected = (self.selected + 1) % self.buttons.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.buttons.is_empty() {
+            self.selected =
+                (self.selected + self.buttons.len() - 1) % self.buttons.len();
+        }
+    }
+
+    /// Press the button with the given id, if it is one of the buttons.
+    pub fn press(&mut self, id: &str) {
+        if let Some((_, action)) =
+            self.buttons.iter().find(|(button_id, _)| button_id == id)
+        {
+            self.pressed = Some(*action);
+        }
+    }
+
+    pub fn press_selected(&mut self) {
+        if let Some((_, action)) = self.buttons.get(self.selected) {
+            self.pressed = Some(*action);
+        }
+    }
+
+    /// Returns the action of the pressed button, if a button was pressed,
+    /// and resets it.
+    pub fn take_pressed(&mut self) -> Option<UiAction> {
+        self.pressed.take()
+    }
+}
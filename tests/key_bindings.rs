@@ -0,0 +1,154 @@
+extern crate amethyst;
+extern crate hello_amethyst_platformer;
+
+use amethyst::input::{
+    Axis,
+    Bindings,
+    Button,
+    ControllerButton,
+    VirtualKeyCode as Key,
+};
+
+use hello_amethyst_platformer::key_bindings::{
+    find_conflict,
+    BindingId,
+    KeyBindingOverrides,
+};
+
+/// A subset of the default bindings, with a controller button and a menu action.
+fn new_bindings() -> Bindings<String, String> {
+    let mut bindings = Bindings::new();
+    bindings
+        .insert_axis("player_x".to_string(), Axis::Emulated {
+            pos: Button::Key(Key::D),
+            neg: Button::Key(Key::A),
+        })
+        .unwrap();
+    for key in &[Key::Escape, Key::Q] {
+        bindings
+            .insert_action_binding("quit".to_string(), vec![Button::Key(*key)])
+            .unwrap();
+    }
+    for button in &[
+        Button::Key(Key::Space),
+        Button::Key(Key::K),
+        Button::Controller(0, ControllerButton::A),
+    ] {
+        bindings
+            .insert_action_binding("player_jump".to_string(), vec![*button])
+            .unwrap();
+    }
+    bindings
+        .insert_action_binding("ui_up".to_string(), vec![Button::Key(Key::Up)])
+        .unwrap();
+    bindings
+}
+
+#[test]
+fn conflicts_with_bindings_not_listed_in_the_options() {
+    let bindings = new_bindings();
+    let conflict = find_conflict(
+        &bindings,
+        BindingId::Action("player_jump"),
+        Button::Key(Key::Up),
+    );
+    assert_eq!(conflict, Some("ui_up".to_string()));
+}
+
+#[test]
+fn conflicts_with_the_other_axis_direction() {
+    let bindings = new_bindings();
+    let conflict = find_conflict(
+        &bindings,
+        BindingId::AxisNeg("player_x"),
+        Button::Key(Key::D),
+    );
+    assert_eq!(conflict, Some("player_x +".to_string()));
+}
+
+#[test]
+fn no_conflict_with_own_keys() {
+    let bindings = new_bindings();
+    assert_eq!(
+        find_conflict(
+            &bindings,
+            BindingId::Action("quit"),
+            Button::Key(Key::Q)
+        ),
+        None
+    );
+    assert_eq!(
+        find_conflict(
+            &bindings,
+            BindingId::Action("player_jump"),
+            Button::Key(Key::W)
+        ),
+        None
+    );
+}
+
+#[test]
+fn rebind_action_replaces_keys_and_keeps_controller_buttons() {
+    let mut bindings = new_bindings();
+    let mut overrides = KeyBindingOverrides::default();
+    let replaced = overrides.rebind(
+        &mut bindings,
+        BindingId::Action("player_jump"),
+        Button::Key(Key::J),
+    );
+    assert_eq!(replaced, vec![Button::Key(Key::Space), Button::Key(Key::K)]);
+    assert_eq!(BindingId::Action("player_jump").keys(&bindings), vec![
+        Button::Key(Key::J)
+    ]);
+    let controller_a = vec![Button::Controller(0, ControllerButton::A)];
+    assert!(bindings
+        .action_bindings("player_jump")
+        .any(|combo| combo.to_vec() == controller_a));
+}
+
+#[test]
+fn rebind_axis_keeps_other_direction() {
+    let mut bindings = new_bindings();
+    let mut overrides = KeyBindingOverrides::default();
+    let replaced = overrides.rebind(
+        &mut bindings,
+        BindingId::AxisPos("player_x"),
+        Button::Key(Key::L),
+    );
+    assert_eq!(replaced, vec![Button::Key(Key::D)]);
+    assert_eq!(BindingId::AxisPos("player_x").keys(&bindings), vec![
+        Button::Key(Key::L)
+    ]);
+    assert_eq!(BindingId::AxisNeg("player_x").keys(&bindings), vec![
+        Button::Key(Key::A)
+    ]);
+}
+
+#[test]
+fn apply_layers_overrides_over_defaults() {
+    let mut overrides = KeyBindingOverrides::default();
+    overrides
+        .actions
+        .insert("quit".to_string(), Button::Key(Key::X));
+    overrides.axes.insert(
+        "player_x".to_string(),
+        (Button::Key(Key::Right), Button::Key(Key::Left)),
+    );
+
+    let mut bindings = new_bindings();
+    overrides.apply(&mut bindings);
+    assert_eq!(BindingId::Action("quit").keys(&bindings), vec![
+        Button::Key(Key::X)
+    ]);
+    assert_eq!(BindingId::AxisPos("player_x").keys(&bindings), vec![
+        Button::Key(Key::Right)
+    ]);
+    assert_eq!(BindingId::AxisNeg("player_x").keys(&bindings), vec![
+        Button::Key(Key::Left)
+    ]);
+    // Bindings without overrides are kept
+    assert_eq!(BindingId::Action("player_jump").keys(&bindings), vec![
+        Button::Key(Key::Space),
+        Button::Key(Key::K)
+    ]);
+}
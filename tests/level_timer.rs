@@ -0,0 +1,51 @@
+extern crate hello_amethyst_platformer;
+
+use hello_amethyst_platformer::level_timer::{LevelTimer, PersonalBest, Split};
+
+const TICK_RATE: u32 = 60;
+
+fn split(checkpoint: &str, ticks: u64) -> Split {
+    Split {
+        checkpoint: checkpoint.to_string(),
+        ticks:      ticks,
+    }
+}
+
+/// Run the timer for the given ticks, splitting at the given checkpoint.
+fn run_to(level_timer: &mut LevelTimer, ticks: u64, checkpoint: &str) {
+    while level_timer.ticks() < ticks {
+        level_timer.tick(TICK_RATE);
+    }
+    level_timer.split(checkpoint);
+}
+
+#[test]
+fn split_delta_compares_the_same_checkpoint() {
+    let mut level_timer = LevelTimer::default();
+    level_timer.reset(Some(PersonalBest {
+        ticks:     600,
+        tick_rate: TICK_RATE,
+        splits:    vec![split("100,40", 120), split("300,40", 300)],
+    }));
+    level_timer.start();
+
+    // Reached in the other order than in the personal best
+    run_to(&mut level_timer, 240, "300,40");
+    assert_eq!(level_timer.delta_seconds(), Some(-1.0));
+    run_to(&mut level_timer, 360, "100,40");
+    assert_eq!(level_timer.delta_seconds(), Some(4.0));
+}
+
+#[test]
+fn split_delta_is_none_for_checkpoint_missing_in_personal_best() {
+    let mut level_timer = LevelTimer::default();
+    level_timer.reset(Some(PersonalBest {
+        ticks:     600,
+        tick_rate: TICK_RATE,
+        splits:    vec![split("100,40", 120)],
+    }));
+    level_timer.start();
+
+    run_to(&mut level_timer, 60, "200,40");
+    assert_eq!(level_timer.delta_seconds(), None);
+}
@@ -0,0 +1,66 @@
+extern crate hello_amethyst_platformer;
+
+use hello_amethyst_platformer::save_data::{
+    SaveData,
+    SaveParseError,
+    SAVE_DATA_VERSION,
+};
+
+#[test]
+fn version_1_save_is_migrated() {
+    let save_raw = r#"(
+        version: 1,
+        unlocked_levels: ["level_1", "level_2"],
+        tallies: {},
+        best_times: {"level_1": 12.5},
+        settings: (volume: Some(0.25)),
+    )"#;
+    let save_data = SaveData::parse(save_raw).expect("Should parse save");
+    assert_eq!(save_data.version, SAVE_DATA_VERSION);
+    assert!(save_data.is_level_unlocked("level_1"));
+    assert!(save_data.is_level_unlocked("level_2"));
+    assert_eq!(save_data.settings.volume, Some(0.25));
+    // Best times in seconds can't be compared with runs in ticks
+    assert!(save_data.best_times.is_empty());
+    assert!(!save_data.is_read_only);
+}
+
+#[test]
+fn version_2_save_keeps_best_times_without_splits() {
+    let save_raw = r#"(
+        version: 2,
+        unlocked_levels: ["level_1"],
+        tallies: {},
+        best_times: {"level_1": (ticks: 600, tick_rate: 60, splits: [120, 300])},
+        settings: (),
+    )"#;
+    let save_data = SaveData::parse(save_raw).expect("Should parse save");
+    assert_eq!(save_data.version, SAVE_DATA_VERSION);
+    let best = &save_data.best_times["level_1"];
+    assert_eq!(best.ticks, 600);
+    assert_eq!(best.tick_rate, 60);
+    assert!(best.splits.is_empty());
+}
+
+#[test]
+fn newer_save_version_is_refused() {
+    let save_raw = format!(
+        "(version: {}, unlocked_levels: [\"level_1\"])",
+        SAVE_DATA_VERSION + 1
+    );
+    match SaveData::parse(&save_raw) {
+        Err(SaveParseError::NewerVersion(version)) => {
+            assert_eq!(version, SAVE_DATA_VERSION + 1)
+        }
+        other => panic!("Should refuse newer version, got {:?}", other),
+    }
+}
+
+#[test]
+fn read_only_save_data_is_not_saved() {
+    let save_data = SaveData {
+        is_read_only: true,
+        ..SaveData::default()
+    };
+    assert!(save_data.save().is_err());
+}
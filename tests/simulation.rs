@@ -0,0 +1,315 @@
+extern crate amethyst;
+extern crate hello_amethyst_platformer;
+
+use amethyst::ecs::Join;
+use amethyst::input::{ControllerAxis, ControllerEvent};
+
+use hello_amethyst_platformer::components::Player;
+use hello_amethyst_platformer::game::MapLoadError;
+use hello_amethyst_platformer::level_exit::LevelExitReached;
+use hello_amethyst_platformer::level_timer::{LevelTimer, TimerState};
+use hello_amethyst_platformer::map_bounds::{CameraBounds, MapBounds};
+use hello_amethyst_platformer::player_input::PlayerInput;
+use hello_amethyst_platformer::settings::load_settings;
+use hello_amethyst_platformer::simulation::Simulation;
+
+/// Frames for the player to fall from their spawn point onto the ground.
+const SETTLE_FRAMES: usize = 120;
+
+fn new_simulation() -> Simulation<'static, 'static> {
+    let settings = load_settings().expect("Should load settings");
+    let mut simulation = Simulation::new(settings);
+    simulation.load_map("map.json").expect("Should load map");
+    simulation.run_frames(SETTLE_FRAMES);
+    simulation
+}
+
+#[test]
+fn player_stands_on_ground() {
+    let mut simulation = new_simulation();
+    let (_, y_before) = simulation.player_position().unwrap();
+    simulation.run_frames(30);
+    let (_, y_after) = simulation.player_position().unwrap();
+    assert_eq!(y_before, y_after);
+}
+
+#[test]
+fn holding_jump_moves_player_up() {
+    let mut simulation = new_simulation();
+    let (_, y_before) = simulation.player_position().unwrap();
+    simulation.set_input(PlayerInput {
+        jump: true,
+        ..Default::default()
+    });
+    simulation.run_frames(30);
+    let (_, y_after) = simulation.player_position().unwrap();
+    assert!(y_after > y_before);
+}
+
+#[test]
+fn holding_right_moves_player_right() {
+    let mut simulation = new_simulation();
+    let (x_before, _) = simulation.player_position().unwrap();
+    simulation.set_input(PlayerInput {
+        x: 1.0,
+        ..Default::default()
+    });
+    simulation.run_frames(30);
+    let (x_after, _) = simulation.player_position().unwrap();
+    assert!(x_after > x_before);
+}
+
+/// Tilt the first controller's left stick horizontally by the given value.
+fn tilt_stick(simulation: &mut Simulation, value: f64) {
+    simulation.send_controller_event(ControllerEvent::ControllerConnected {
+        which: 0,
+    });
+    simulation.send_controller_event(ControllerEvent::ControllerAxisMoved {
+        which: 0,
+        axis:  ControllerAxis::LeftX,
+        value: value,
+    });
+}
+
+/// Returns how far the player moves horizontally in 30 frames,
+/// with the left stick tilted by the given value.
+fn distance_with_stick(value: f64) -> f32 {
+    let mut simulation = new_simulation();
+    let (x_before, _) = simulation.player_position().unwrap();
+    tilt_stick(&mut simulation, value);
+    simulation.run_frames(30);
+    let (x_after, _) = simulation.player_position().unwrap();
+    x_after - x_before
+}
+
+#[test]
+fn tilting_stick_right_moves_player_right() {
+    assert!(distance_with_stick(1.0) > 0.0);
+}
+
+#[test]
+fn stick_inside_deadzone_does_not_move_player() {
+    assert_eq!(distance_with_stick(0.1), 0.0);
+}
+
+#[test]
+fn stick_drift_does_not_start_level_timer() {
+    let mut simulation = new_simulation();
+    tilt_stick(&mut simulation, 0.1);
+    simulation.run_frames(30);
+    let state = simulation.world.read_resource::<LevelTimer>().state();
+    assert_eq!(state, TimerState::Waiting);
+}
+
+#[test]
+fn half_tilted_stick_moves_player_slower() {
+    let half = distance_with_stick(0.5);
+    let full = distance_with_stick(1.0);
+    assert!(half > 0.0);
+    assert!(half < full);
+}
+
+#[test]
+fn camera_view_stays_inside_map() {
+    let mut simulation = new_simulation();
+    let bounds = *simulation.world.read_resource::<MapBounds>();
+    let settings = load_settings().expect("Should load settings");
+    let (view_w, view_h) = (settings.camera.size.0, settings.camera.size.1);
+    simulation.set_input(PlayerInput {
+        x: -1.0,
+        ..Default::default()
+    });
+    // Keep walking against the left edge, with several ticks per frame,
+    // so the camera rests on the bounds during most of the frames
+    let frame_seconds = simulation.tick_seconds() * 3.5;
+    for _ in 0..120 {
+        assert!(simulation.update(frame_seconds) >= 3);
+        let (x, y) = simulation.camera_position().unwrap();
+        if view_w < bounds.right - bounds.left {
+            assert!(x >= bounds.left && x + view_w <= bounds.right);
+        }
+        if view_h < bounds.top - bounds.bottom {
+            assert!(y >= bounds.bottom && y + view_h <= bounds.top);
+        }
+    }
+}
+
+#[test]
+fn level_exit_is_reached_with_several_ticks_per_frame() {
+    let settings = load_settings().expect("Should load settings");
+    let mut simulation = Simulation::new(settings);
+    simulation
+        .load_map("test_maps/level_exit.json")
+        .expect("Should load map");
+    simulation.set_input(PlayerInput {
+        x: 1.0,
+        ..Default::default()
+    });
+    let frame_seconds = simulation.tick_seconds() * 2.5;
+    let mut reached = None;
+    for _ in 0..600 {
+        assert!(simulation.update(frame_seconds) >= 2);
+        reached = simulation
+            .world
+            .read_resource::<LevelExitReached>()
+            .level()
+            .map(String::from);
+        if reached.is_some() {
+            break;
+        }
+    }
+    assert_eq!(reached, Some("level_2".to_string()));
+}
+
+/// Returns a simulation of the hazard test map, after the player has landed
+/// right next to the hazard.
+fn new_hazard_simulation() -> Simulation<'static, 'static> {
+    let settings = load_settings().expect("Should load settings");
+    let mut simulation = Simulation::new(settings);
+    simulation
+        .load_map("test_maps/hazard.json")
+        .expect("Should load map");
+    simulation.run_frames(SETTLE_FRAMES);
+    simulation
+}
+
+fn player_is_dead(simulation: &Simulation) -> bool {
+    let players = simulation.world.read_storage::<Player>();
+    (&players).join().any(|player| player.is_dead())
+}
+
+#[test]
+fn player_next_to_hazard_does_not_die() {
+    let mut simulation = new_hazard_simulation();
+    simulation.run_frames(60);
+    assert!(!player_is_dead(&simulation));
+}
+
+#[test]
+fn player_walking_into_hazard_dies() {
+    let mut simulation = new_hazard_simulation();
+    simulation.set_input(PlayerInput {
+        x: 1.0,
+        ..Default::default()
+    });
+    simulation.run_frames(30);
+    assert!(player_is_dead(&simulation));
+}
+
+#[test]
+fn player_walking_into_solid_hazard_dies() {
+    let settings = load_settings().expect("Should load settings");
+    let mut simulation = Simulation::new(settings);
+    simulation
+        .load_map("test_maps/solid_hazard.json")
+        .expect("Should load map");
+    simulation.run_frames(SETTLE_FRAMES);
+    assert!(!player_is_dead(&simulation));
+    simulation.set_input(PlayerInput {
+        x: 1.0,
+        ..Default::default()
+    });
+    simulation.run_frames(60);
+    assert!(player_is_dead(&simulation));
+}
+
+/// Returns a simulation with double jumps enabled, after the player has landed.
+fn new_double_jump_simulation() -> Simulation<'static, 'static> {
+    let mut settings = load_settings().expect("Should load settings");
+    settings.player.is_double_jump_enabled = true;
+    let mut simulation = Simulation::new(settings);
+    simulation.load_map("map.json").expect("Should load map");
+    simulation.run_frames(SETTLE_FRAMES);
+    simulation
+}
+
+/// Returns `(is_in_air, has_double_jumped)` of the player.
+fn player_jump_state(simulation: &Simulation) -> (bool, bool) {
+    let players = simulation.world.read_storage::<Player>();
+    let player = (&players).join().next().unwrap();
+    (player.is_in_air, player.has_double_jumped)
+}
+
+/// Jump, and release the jump button after a few frames.
+fn jump_and_release(simulation: &mut Simulation) {
+    simulation.set_input(PlayerInput {
+        jump: true,
+        ..Default::default()
+    });
+    simulation.run_frames(10);
+    simulation.set_input(PlayerInput::default());
+}
+
+#[test]
+fn jump_pressed_shortly_before_landing_is_a_ground_jump() {
+    // Find the frame, on which the player lands after jumping
+    let mut reference = new_double_jump_simulation();
+    jump_and_release(&mut reference);
+    let mut frames_until_landing = 0;
+    while player_jump_state(&reference).0 {
+        reference.step();
+        frames_until_landing += 1;
+        assert!(frames_until_landing < 600, "Player should land");
+    }
+
+    // Press jump a few frames before landing
+    let mut simulation = new_double_jump_simulation();
+    jump_and_release(&mut simulation);
+    simulation.run_frames(frames_until_landing - 3);
+    simulation.set_input(PlayerInput {
+        jump: true,
+        ..Default::default()
+    });
+    for _ in 0..3 {
+        simulation.step();
+        let (_, has_double_jumped) = player_jump_state(&simulation);
+        assert!(!has_double_jumped, "Should not spend the double jump");
+    }
+    simulation.run_frames(3);
+    let (is_in_air, has_double_jumped) = player_jump_state(&simulation);
+    let (_, velocity_y) = simulation.player_velocity().unwrap();
+    assert!(is_in_air && velocity_y > 0.0, "Should jump after landing");
+    assert!(!has_double_jumped, "Double jump should still be available");
+}
+
+#[test]
+fn missing_tileset_image_is_reported_as_io_error() {
+    let settings = load_settings().expect("Should load settings");
+    let mut simulation = Simulation::new(settings);
+    match simulation.load_map("test_maps/missing_tileset_image.json") {
+        Err(MapLoadError::Io { path, .. }) => {
+            assert!(path.ends_with("Missing.png"))
+        }
+        other => panic!("Should be an Io error, got {:?}", other),
+    }
+}
+
+#[test]
+fn map_without_tiles_clears_previous_map_bounds() {
+    let mut simulation = new_simulation();
+    simulation
+        .load_map("test_maps/no_tiles.json")
+        .expect("Should load map");
+    assert!(!simulation.world.res.has_value::<MapBounds>());
+    let camera_bounds = *simulation.world.read_resource::<CameraBounds>();
+    assert_eq!(camera_bounds.bounds, None);
+}
+
+#[test]
+fn camera_bounds_object_covers_the_area_drawn_in_tiled() {
+    let settings = load_settings().expect("Should load settings");
+    let mut simulation = Simulation::new(settings);
+    simulation
+        .load_map("test_maps/camera_bounds.json")
+        .expect("Should load map");
+    let camera_bounds = *simulation.world.read_resource::<CameraBounds>();
+    assert_eq!(
+        camera_bounds.bounds,
+        Some(MapBounds {
+            left:   32.0,
+            right:  352.0,
+            bottom: 64.0,
+            top:    256.0,
+        })
+    );
+}
@@ -117,8 +117,15 @@ class Object:
         data["pos"]  = self.pos
         data["size"] = self.size
         data["properties"] = { **properties_of(self.layer), **properties_of(self.obj) } # Merge layer and object properties, object properties getting precedence
+        if self.obj.shape() == MapObject.Polyline:
+            data["polyline"] = self.polyline()
         return data
 
+    def polyline(self):
+        # Points relative to the object's position, Y pointing UP
+        polygon = self.obj.polygon()
+        return [{ "x": polygon.at(i).x(), "y": -polygon.at(i).y() } for i in range(polygon.size())]
+
 
     def display(self):
         out  = "\nOBJECT"